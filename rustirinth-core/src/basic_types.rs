@@ -1,6 +1,4 @@
 #![allow(dead_code)]
-use gtk;
-use cairo;
 
 use std::fmt::Debug;
 use std::marker::Copy;
@@ -16,6 +14,79 @@ pub enum LabyrinthError {
     ConversionError { value: String },
     #[fail(display = "An internal error occurred")]
     InternalError,
+    #[fail(display = "Unknown heuristic \"{}\"", name)]
+    UnknownHeuristic { name: String },
+    #[fail(display = "Unknown neighbourhood \"{}\"", name)]
+    UnknownNeighbourhood { name: String },
+    #[fail(display = "Unknown corner cutting policy \"{}\"", name)]
+    UnknownCornerCutting { name: String },
+    #[fail(display = "Unknown maze generator \"{}\"", name)]
+    UnknownGenerator { name: String },
+    #[fail(display = "Malformed labyrinth file, line {} column {}: {}", line, column, reason)]
+    MalformedFile { line: usize, column: usize, reason: String },
+    #[fail(
+        display = "The labyrinth with {}x{} boxes does not fit into the window, zoom out with the mouse wheel to see all boxes",
+        x_box_cnt,
+        y_box_cnt
+    )]
+    DoesNotFit { x_box_cnt: u32, y_box_cnt: u32 },
+    #[fail(display = "Invalid window geometry \"{}\", expected WIDTHxHEIGHT", value)]
+    InvalidGeometry { value: String },
+    #[fail(display = "The labyrinth needs at least one column and one row")]
+    EmptyBoard,
+    #[fail(display = "The box size {} is smaller than the minimum of {}", box_size, minimum)]
+    BoxSizeTooSmall { box_size: u32, minimum: u32 },
+    #[fail(display = "The box size {} is larger than the maximum of {}", box_size, maximum)]
+    BoxSizeTooLarge { box_size: u32, maximum: u32 },
+    #[fail(display = "The labyrinth can have at most {} columns and rows, not {}", maximum, box_cnt)]
+    TooManyBoxes { box_cnt: u32, maximum: u32 },
+    #[fail(display = "The labyrinth with {}x{} boxes of size {} is too large to be drawn", x_box_cnt, y_box_cnt, box_size)]
+    BoardTooLarge { x_box_cnt: u32, y_box_cnt: u32, box_size: u32 },
+    #[fail(display = "The labyrinth needs a start and an end")]
+    MissingEndpoint,
+    #[fail(display = "There is no way from the start to the end")]
+    NoPath,
+    #[fail(display = "Unknown export format \"{}\", expected png, svg or pdf", name)]
+    UnknownExportFormat { name: String },
+    #[fail(display = "Invalid scale \"{}\", expected a positive number", value)]
+    InvalidScale { value: String },
+    #[fail(display = "Invalid threshold \"{}\", expected a number from 0 to 1", value)]
+    InvalidThreshold { value: String },
+    #[fail(display = "The image with {}x{} pixels is too small for {}x{} boxes", width, height, x_box_cnt, y_box_cnt)]
+    ImageTooSmall { width: u32, height: u32, x_box_cnt: u32, y_box_cnt: u32 },
+    #[fail(display = "Cairo failed: {}", reason)]
+    CairoFailed { reason: String },
+}
+
+// warnings only inform about something which still works, recoverable errors
+// only affect the action which caused them, after a fatal one the board cannot
+// be trusted any more and the program has to end
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+    Warning,
+    Recoverable,
+    Fatal,
+}
+
+impl LabyrinthError {
+    pub fn severity(&self) -> Severity {
+        match *self {
+            LabyrinthError::CouldNotGetDefaultScreen | LabyrinthError::InternalError => Severity::Fatal,
+            LabyrinthError::DoesNotFit { .. } => Severity::Warning,
+            _ => Severity::Recoverable,
+        }
+    }
+}
+
+// the most severe LabyrinthError in the chain of causes, all other errors
+// (e.g. from reading or writing files) are recoverable
+pub fn severity(error: &Error) -> Severity {
+    error
+        .causes()
+        .filter_map(|cause| cause.downcast_ref::<LabyrinthError>())
+        .map(LabyrinthError::severity)
+        .max()
+        .unwrap_or(Severity::Recoverable)
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -44,52 +115,6 @@ pub trait IsARectangle<T> {
     fn height(&self) -> T;
 }
 
-impl IsARectangle<i32> for gtk::Rectangle {
-    fn from_tuple((x, y, width, height): (i32, i32, i32, i32)) -> gtk::Rectangle {
-        gtk::Rectangle {
-            x,
-            y,
-            width,
-            height,
-        }
-    }
-    fn x(&self) -> i32 {
-        self.x
-    }
-    fn y(&self) -> i32 {
-        self.y
-    }
-    fn width(&self) -> i32 {
-        self.width
-    }
-    fn height(&self) -> i32 {
-        self.height
-    }
-}
-
-impl IsARectangle<i32> for cairo::RectangleInt {
-    fn from_tuple((x, y, width, height): (i32, i32, i32, i32)) -> cairo::RectangleInt {
-        cairo::RectangleInt {
-            x,
-            y,
-            width,
-            height,
-        }
-    }
-    fn x(&self) -> i32 {
-        self.x
-    }
-    fn y(&self) -> i32 {
-        self.y
-    }
-    fn width(&self) -> i32 {
-        self.width
-    }
-    fn height(&self) -> i32 {
-        self.height
-    }
-}
-
 impl<T> IsARectangle<T> for (T, T, T, T)
 where
     T: Copy,
@@ -318,7 +343,9 @@ where
     fn get_white() -> Self;
     fn get_black() -> Self;
     fn get_blue() -> Self;
+    fn get_green() -> Self;
     fn get_red() -> Self;
+    fn get_yellow() -> Self;
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
@@ -365,9 +392,17 @@ where
         GeneralColor::<T>::from_tuple((0.into(), 0.into(), 255.into()))
     }
 
+    fn get_green() -> GeneralColor<T> {
+        GeneralColor::<T>::from_tuple((0.into(), 255.into(), 0.into()))
+    }
+
     fn get_red() -> GeneralColor<T> {
         GeneralColor::<T>::from_tuple((255.into(), 0.into(), 0.into()))
     }
+
+    fn get_yellow() -> GeneralColor<T> {
+        GeneralColor::<T>::from_tuple((255.into(), 255.into(), 0.into()))
+    }
 }
 
 pub type Color = GeneralColor<f64>;
@@ -375,68 +410,8 @@ pub type Color = GeneralColor<f64>;
 #[cfg(test)]
 mod tests {
 
-    use cairo;
-    use gtk;
     use super::*;
 
-    #[test]
-    fn from_cairo_ok() {
-        let cairo_rectangle = cairo::RectangleInt {
-            x: 1,
-            y: 2,
-            width: 3,
-            height: 4,
-        };
-        let rectangle = Rectangle::from(&cairo_rectangle).unwrap();
-        assert_eq!(
-            rectangle,
-            Rectangle {
-                x: 1,
-                y: 2,
-                width: 3,
-                height: 4,
-            }
-        );
-    }
-
-    #[test]
-    fn from_cairo_err() {
-        let cairo_rectangle = cairo::RectangleInt {
-            x: -1,
-            y: 2,
-            width: 3,
-            height: 4,
-        };
-        let rectangle = Rectangle::from(&cairo_rectangle);
-        assert!(rectangle.is_err());
-        let error = rectangle.err().unwrap();
-        let error_string = format!("{}", error);
-        assert_eq!(
-            error_string,
-            "Conversion error or overflow while converting \"-1\""
-        );
-    }
-
-    #[test]
-    fn from_gtk_ok() {
-        let gtk_rectangle = gtk::Rectangle {
-            x: 1,
-            y: 2,
-            width: 3,
-            height: 4,
-        };
-        let rectangle = Rectangle::from(&gtk_rectangle).unwrap();
-        assert_eq!(
-            rectangle,
-            Rectangle {
-                x: 1,
-                y: 2,
-                width: 3,
-                height: 4,
-            }
-        );
-    }
-
     #[test]
     fn to_float_tuple() {
         type Ftuple = (f64, f64, f64, f64);
@@ -487,4 +462,18 @@ mod tests {
         let float_tuple = rectangle.approx_to::<f64, (f64, f64, f64, f64)>().unwrap();
         assert_eq!(float_tuple, ((1.0, 4294967295.0, 3.0, 4.0)));
     }
+
+    #[test]
+    fn severity_of_cause_chain() {
+        use std::io;
+        let conversion: Error = convert::<i32, u32>(-1).unwrap_err();
+        assert_eq!(severity(&conversion), Severity::Recoverable);
+        let io_error: Error = io::Error::new(io::ErrorKind::NotFound, "gone").into();
+        assert_eq!(severity(&io_error), Severity::Recoverable);
+        let internal: Error = LabyrinthError::InternalError.into();
+        let wrapped: Error = internal.context("Could not draw").into();
+        assert_eq!(severity(&wrapped), Severity::Fatal);
+        let does_not_fit: Error = LabyrinthError::DoesNotFit { x_box_cnt: 80, y_box_cnt: 60 }.into();
+        assert_eq!(severity(&does_not_fit), Severity::Warning);
+    }
 }
@@ -0,0 +1,312 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use failure::Error;
+use labyrinth::{BoxState, Labyrinth};
+use basic_types::LabyrinthError;
+use grid::Grid;
+
+/* A labyrinth is stored as plain text:
+
+   rustirinth 1
+   box-size 32
+   size 5 3
+   #####
+   #S.E#
+   #####
+
+   followed by one line per row of boxes with one character per box. */
+const MAGIC: &str = "rustirinth";
+const VERSION: u32 = 1;
+// larger boards would not fit into memory
+pub const MAXIMUM_BOX_COUNT: u32 = 4096;
+
+pub fn box_state_to_char(state: BoxState) -> char {
+    match state {
+        BoxState::Empty => '.',
+        BoxState::Labyrinth => '#',
+        BoxState::Start => 'S',
+        BoxState::End => 'E',
+        BoxState::Road => '=',
+        BoxState::Mud => '%',
+        BoxState::Water => '~',
+    }
+}
+
+pub fn char_to_box_state(character: char) -> Option<BoxState> {
+    match character {
+        '.' => Some(BoxState::Empty),
+        '#' => Some(BoxState::Labyrinth),
+        'S' => Some(BoxState::Start),
+        'E' => Some(BoxState::End),
+        '=' => Some(BoxState::Road),
+        '%' => Some(BoxState::Mud),
+        '~' => Some(BoxState::Water),
+        _ => None,
+    }
+}
+
+pub fn write<W: Write>(labyrinth: &Labyrinth, writer: &mut W) -> Result<(), Error> {
+    writeln!(writer, "{} {}", MAGIC, VERSION)?;
+    writeln!(writer, "box-size {}", labyrinth.grid.box_size)?;
+    writeln!(writer, "size {} {}", labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt)?;
+    for y in 0..labyrinth.grid.y_box_cnt as usize {
+        let row: String = (0..labyrinth.grid.x_box_cnt as usize)
+            .map(|x| box_state_to_char(labyrinth.marked[Dim(x, y)].state))
+            .collect();
+        writeln!(writer, "{}", row)?;
+    }
+    Ok(())
+}
+
+// the rows of the file with '*' on every box of the way between start and end
+pub fn render(labyrinth: &Labyrinth, path: &[(u32, u32)]) -> String {
+    let mut rows: Vec<Vec<char>> = (0..labyrinth.grid.y_box_cnt as usize)
+        .map(|y| {
+            (0..labyrinth.grid.x_box_cnt as usize)
+                .map(|x| box_state_to_char(labyrinth.marked[Dim(x, y)].state))
+                .collect()
+        })
+        .collect();
+    for &(x, y) in path {
+        let character = &mut rows[y as usize][x as usize];
+        if *character != 'S' && *character != 'E' {
+            *character = '*';
+        }
+    }
+    rows.into_iter()
+        .map(|row| row.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+fn malformed(line: usize, column: usize, reason: &str) -> Error {
+    LabyrinthError::MalformedFile {
+        line,
+        column,
+        reason: reason.to_string(),
+    }.into()
+}
+
+// splits a header line like "size 20 15" into its values, which have to
+// be numbers in the given range
+fn parse_header(
+    number: usize,
+    line: Option<&str>,
+    keyword: &str,
+    value_count: usize,
+    range: RangeInclusive<u32>,
+) -> Result<Vec<u32>, Error> {
+    let line = match line {
+        Some(line) => line,
+        None => return Err(malformed(number, 1, &format!("expected \"{}\"", keyword))),
+    };
+    if !line.starts_with(keyword) {
+        return Err(malformed(number, 1, &format!("expected \"{}\"", keyword)));
+    }
+    let mut values = Vec::with_capacity(value_count);
+    let mut column = keyword.len() + 1;
+    for token in line[keyword.len()..].split(' ') {
+        if !token.is_empty() {
+            match token.parse::<u32>() {
+                Ok(_) if values.len() >= value_count => return Err(malformed(number, column, "too many values")),
+                Ok(value) if range.contains(&value) => values.push(value),
+                Ok(_) => {
+                    let reason = format!("expected a number from {} to {}", range.start(), range.end());
+                    return Err(malformed(number, column, &reason));
+                }
+                _ => return Err(malformed(number, column, "expected a positive number")),
+            }
+        }
+        column += token.len() + 1;
+    }
+    if values.len() < value_count {
+        return Err(malformed(number, line.len() + 1, "too few values"));
+    }
+    Ok(values)
+}
+
+pub fn read<R: BufRead>(reader: R) -> Result<Labyrinth, Error> {
+    let lines = reader.lines().collect::<Result<Vec<String>, _>>()?;
+    let mut lines = lines.iter().map(|line| line.trim_end_matches('\r'));
+    let version = parse_header(1, lines.next(), MAGIC, 1, 1..=u32::MAX)?;
+    if version[0] != VERSION {
+        return Err(malformed(1, MAGIC.len() + 2, "unsupported version"));
+    }
+    // smaller boxes have no inside left between their borders
+    let box_size = parse_header(2, lines.next(), "box-size", 1, Labyrinth::MINIMUM_BOX_SIZE..=u32::MAX)?[0];
+    let size = parse_header(3, lines.next(), "size", 2, 1..=MAXIMUM_BOX_COUNT)?;
+    // the board has to fit into the pixel coordinates
+    if Grid::pixel_size(size[0], box_size).is_none() || Grid::pixel_size(size[1], box_size).is_none() {
+        return Err(malformed(3, "size ".len() + 1, "too many boxes for the box size"));
+    }
+    let (x_box_cnt, y_box_cnt) = (size[0] as usize, size[1] as usize);
+    let mut states = Array::<BoxState>::default(Dim(x_box_cnt, y_box_cnt));
+    let mut start = false;
+    let mut end = false;
+    for y in 0..y_box_cnt {
+        let number = y + 4;
+        let line = match lines.next() {
+            Some(line) => line,
+            None => return Err(malformed(number, 1, &format!("expected {} rows", y_box_cnt))),
+        };
+        let mut row_length = 0;
+        for (x, character) in line.chars().enumerate() {
+            let state = match char_to_box_state(character) {
+                Some(_) if x >= x_box_cnt => return Err(malformed(number, x + 1, "row too long")),
+                Some(state) => state,
+                None => return Err(malformed(number, x + 1, &format!("unknown box '{}'", character))),
+            };
+            let seen = match state {
+                BoxState::Start => Some(&mut start),
+                BoxState::End => Some(&mut end),
+                _ => None,
+            };
+            if let Some(seen) = seen {
+                if *seen {
+                    return Err(malformed(number, x + 1, "start and end may only be set once"));
+                }
+                *seen = true;
+            }
+            states[Dim(x, y)] = state;
+            row_length += 1;
+        }
+        if row_length < x_box_cnt {
+            return Err(malformed(number, row_length + 1, "row too short"));
+        }
+    }
+    for (index, line) in lines.enumerate() {
+        if !line.trim().is_empty() {
+            return Err(malformed(index + y_box_cnt + 4, 1, "too many rows"));
+        }
+    }
+    Labyrinth::from_states(box_size, &states)
+}
+
+pub fn load(path: &Path) -> Result<Labyrinth, Error> {
+    File::open(path)
+        .map_err(Error::from)
+        .and_then(|file| read(BufReader::new(file)))
+        .map_err(|error| error.context(format!("Could not load \"{}\"", path.display())).into())
+}
+
+pub fn save(labyrinth: &Labyrinth, path: &Path) -> Result<(), Error> {
+    File::create(path)
+        .map_err(Error::from)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write(labyrinth, &mut writer)?;
+            Ok(writer.flush()?)
+        })
+        .map_err(|error| error.context(format!("Could not save \"{}\"", path.display())).into())
+}
+
+// writes into a temporary file next to the target which then replaces it, so
+// the target holds either the old or the new contents even after a crash
+pub fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let mut temporary_name = path.file_name().unwrap_or_default().to_os_string();
+    temporary_name.push(".tmp");
+    let temporary_path = path.with_file_name(temporary_name);
+    let written = File::create(&temporary_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temporary_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary_path);
+    }
+    written.map_err(|error| Error::from(error).context(format!("Could not write \"{}\"", path.display())).into())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::io::Cursor;
+    use grid::PointInGridCoordinates;
+
+    fn read_str(text: &str) -> Result<Labyrinth, Error> {
+        read(Cursor::new(text.as_bytes()))
+    }
+
+    fn error_position(text: &str) -> (usize, usize) {
+        match read_str(text).unwrap_err().downcast::<LabyrinthError>() {
+            Ok(LabyrinthError::MalformedFile { line, column, .. }) => (line, column),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "rustirinth 1\nbox-size 32\nsize 6 3\n######\n#S%~E#\n#==..#\n";
+        let labyrinth = read_str(text).unwrap();
+        assert_eq!((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt, labyrinth.grid.box_size), (6, 3, 32));
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(1, 1)));
+        assert_eq!(labyrinth.end, Some(PointInGridCoordinates::new(4, 1)));
+        assert_eq!(labyrinth.marked[Dim(2, 1)].state, BoxState::Mud);
+        let mut written = Vec::new();
+        write(&labyrinth, &mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), text);
+    }
+
+    #[test]
+    fn path_is_computed() {
+        let labyrinth = read_str("rustirinth 1\nbox-size 16\nsize 3 1\nS.E\n").unwrap();
+        assert_eq!(labyrinth.solution.unwrap().path.len(), 3);
+    }
+
+    #[test]
+    fn render_path() {
+        let labyrinth = read_str("rustirinth 1\nbox-size 16\nsize 4 3\nS#..\n.#.E\n....\n").unwrap();
+        let path = labyrinth.solution.clone().unwrap().path;
+        assert_eq!(render(&labyrinth, &path), "S#..\n*#*E\n***.\n");
+        assert_eq!(render(&labyrinth, &[]), "S#..\n.#.E\n....\n");
+    }
+
+    #[test]
+    fn accepts_windows_line_endings() {
+        assert!(read_str("rustirinth 1\r\nbox-size 16\r\nsize 2 1\r\n#.\r\n\r\n").is_ok());
+    }
+
+    #[test]
+    fn malformed_header() {
+        assert_eq!(error_position(""), (1, 1));
+        assert_eq!(error_position("labyrinth 1\n"), (1, 1));
+        assert_eq!(error_position("rustirinth 2\n"), (1, 12));
+        assert_eq!(error_position("rustirinth 1\nbox-size large\n"), (2, 10));
+        assert_eq!(error_position("rustirinth 1\nbox-size 16\nsize 4\n"), (3, 7));
+        assert_eq!(error_position("rustirinth 1\nbox-size 16\nsize 4 0\n"), (3, 8));
+        assert_eq!(error_position("rustirinth 1\nbox-size 16\nsize 4 1 1\n"), (3, 10));
+        assert_eq!(error_position("rustirinth 1\nbox-size 1\nsize 4 1\n#...\n"), (2, 10));
+        assert_eq!(error_position("rustirinth 1\nbox-size 4000000000\nsize 2 1\n#.\n"), (3, 6));
+        assert_eq!(error_position("rustirinth 1\nbox-size 16\nsize 5000 1\n"), (3, 6));
+        assert_eq!(error_position("rustirinth 1\nbox-size 16\nsize 4 5000\n"), (3, 8));
+        assert!(read_str("rustirinth 1\nbox-size 4000000000\nsize 1 1\n#\n").is_ok());
+    }
+
+    #[test]
+    fn malformed_rows() {
+        let header = "rustirinth 1\nbox-size 16\nsize 4 2\n";
+        assert_eq!(error_position(&format!("{}....\n", header)), (5, 1));
+        assert_eq!(error_position(&format!("{}....\n..x.\n", header)), (5, 3));
+        assert_eq!(error_position(&format!("{}....\n...\n", header)), (5, 4));
+        assert_eq!(error_position(&format!("{}.....\n....\n", header)), (4, 5));
+        assert_eq!(error_position(&format!("{}S...\n..S.\n", header)), (5, 3));
+        assert_eq!(error_position(&format!("{}....\n....\n....\n", header)), (6, 1));
+    }
+
+    #[test]
+    fn atomic_write_replaces_file() {
+        let directory = ::std::env::temp_dir().join(format!("rustirinth-test-{}", ::std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("board.txt");
+        write_atomically(&path, b"first").unwrap();
+        write_atomically(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+        assert!(write_atomically(&directory.join("missing").join("board.txt"), b"third").is_err());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
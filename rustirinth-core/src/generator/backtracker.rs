@@ -0,0 +1,41 @@
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Randomized depth-first search: walks to a random unvisited neighbour as long
+// as there is one and backtracks otherwise. Produces long, winding corridors.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RecursiveBacktracker;
+
+impl MazeGenerator for RecursiveBacktracker {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        if cells.is_empty() {
+            return;
+        }
+        let mut visited = Array::<bool>::from_elem(Dim(cells.width, cells.height), false);
+        let start = cells.random_cell(rng);
+        visited[Dim(start.0, start.1)] = true;
+        cells.carve(start);
+        // an explicit stack, the recursion would be too deep on large boards
+        let mut stack = vec![start];
+        while let Some(&current) = stack.last() {
+            let unvisited: Vec<(usize, usize)> = cells
+                .neighbours(current)
+                .into_iter()
+                .filter(|&(x, y)| !visited[Dim(x, y)])
+                .collect();
+            match rng.choose(&unvisited) {
+                Some(&next) => {
+                    visited[Dim(next.0, next.1)] = true;
+                    cells.connect(current, next);
+                    stack.push(next);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use ndarray::Array2 as Array;
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Every cell opens the wall to the north or to the west. The simplest
+// algorithm, but with a strong diagonal bias and two open borders.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BinaryTree;
+
+impl MazeGenerator for BinaryTree {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        for (x, y) in cells.all() {
+            cells.carve((x, y));
+            let mut candidates = Vec::with_capacity(2);
+            if y > 0 {
+                candidates.push((x, y - 1));
+            }
+            if x > 0 {
+                candidates.push((x - 1, y));
+            }
+            if let Some(&next) = rng.choose(&candidates) {
+                cells.connect((x, y), next);
+            }
+        }
+    }
+}
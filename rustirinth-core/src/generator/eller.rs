@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+use ndarray::Array2 as Array;
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Eller's algorithm: creates the maze row by row and only remembers which
+// cells of the current row are connected. Every set has to continue in the
+// next row, the last row joins all remaining sets.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Eller;
+
+impl MazeGenerator for Eller {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        if cells.is_empty() {
+            return;
+        }
+        let (width, height) = (cells.width, cells.height);
+        let mut sets: Vec<Option<usize>> = vec![None; width];
+        let mut next_set = 0;
+        for y in 0..height {
+            let mut row = Vec::with_capacity(width);
+            for (x, set) in sets.iter().enumerate() {
+                cells.carve((x, y));
+                row.push(set.unwrap_or_else(|| {
+                    next_set += 1;
+                    next_set
+                }));
+            }
+            let last_row = y + 1 == height;
+            for x in 1..width {
+                if row[x - 1] != row[x] && (last_row || rng.gen::<bool>()) {
+                    cells.connect((x - 1, y), (x, y));
+                    let (joined, removed) = (row[x - 1], row[x]);
+                    for set in row.iter_mut().filter(|set| **set == removed) {
+                        *set = joined;
+                    }
+                }
+            }
+            if last_row {
+                break;
+            }
+            // BTreeMap instead of HashMap: the order must not depend on
+            // anything but the seed
+            let mut members = BTreeMap::<usize, Vec<usize>>::new();
+            for (x, &set) in row.iter().enumerate() {
+                members.entry(set).or_default().push(x);
+            }
+            sets = vec![None; width];
+            for (set, mut columns) in members {
+                rng.shuffle(&mut columns);
+                let down = rng.gen_range(1, columns.len() + 1);
+                for &x in &columns[..down] {
+                    cells.connect((x, y), (x, y + 1));
+                    sets[x] = Some(set);
+                }
+            }
+        }
+    }
+}
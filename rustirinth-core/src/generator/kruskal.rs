@@ -0,0 +1,93 @@
+use std::mem::swap;
+use ndarray::Array2 as Array;
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Randomized Kruskal: opens the walls in random order, as long as they
+// separate two cells which are not yet connected. The texture is similar to
+// Prim's, but the maze grows everywhere at once.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Kruskal;
+
+impl MazeGenerator for Kruskal {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        if cells.is_empty() {
+            return;
+        }
+        let mut walls = Vec::with_capacity(2 * cells.len());
+        for (x, y) in cells.all() {
+            cells.carve((x, y));
+            if x + 1 < cells.width {
+                walls.push(((x, y), (x + 1, y)));
+            }
+            if y + 1 < cells.height {
+                walls.push(((x, y), (x, y + 1)));
+            }
+        }
+        rng.shuffle(&mut walls);
+        let mut sets = DisjointSets::new(cells.len());
+        for (from, to) in walls {
+            if sets.union(cells.index(from), cells.index(to)) {
+                cells.connect(from, to);
+            }
+        }
+    }
+}
+
+// union-find with path halving and union by rank
+#[derive(Debug, Clone)]
+struct DisjointSets {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> DisjointSets {
+        DisjointSets {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+        }
+    }
+    fn find(&mut self, mut element: usize) -> usize {
+        while self.parent[element] != element {
+            self.parent[element] = self.parent[self.parent[element]];
+            element = self.parent[element];
+        }
+        element
+    }
+    // returns false if both elements already were in the same set
+    fn union(&mut self, first: usize, second: usize) -> bool {
+        let mut first = self.find(first);
+        let mut second = self.find(second);
+        if first == second {
+            return false;
+        }
+        if self.rank[first] < self.rank[second] {
+            swap(&mut first, &mut second);
+        }
+        self.parent[second] = first;
+        if self.rank[first] == self.rank[second] {
+            self.rank[first] += 1;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn union_find() {
+        let mut sets = DisjointSets::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(1, 4));
+        assert_eq!(sets.find(0), sets.find(3));
+        assert!(sets.find(2) != sets.find(0));
+    }
+}
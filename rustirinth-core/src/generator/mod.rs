@@ -0,0 +1,267 @@
+use std::str::FromStr;
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use rand::Rng;
+use labyrinth::{BoxState, LabyrinthEntry};
+use basic_types::LabyrinthError;
+
+mod backtracker;
+mod binary_tree;
+mod eller;
+mod kruskal;
+mod prim;
+mod sidewinder;
+mod wilson;
+
+pub use self::backtracker::RecursiveBacktracker;
+pub use self::binary_tree::BinaryTree;
+pub use self::eller::Eller;
+pub use self::kruskal::Kruskal;
+pub use self::prim::Prim;
+pub use self::sidewinder::Sidewinder;
+pub use self::wilson::Wilson;
+
+// fills the whole board with a perfect maze: every cell can be reached from
+// every other cell on exactly one way
+pub trait MazeGenerator {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R);
+}
+
+// Boxes with two odd coordinates are the cells of a maze, the boxes between
+// two cells are removed to connect them. All other boxes stay walls.
+pub struct Cells<'a> {
+    marked: &'a mut Array<LabyrinthEntry>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<'a> Cells<'a> {
+    pub fn new(marked: &'a mut Array<LabyrinthEntry>) -> Cells<'a> {
+        marked.fill(LabyrinthEntry {
+            state: BoxState::Labyrinth,
+            ..LabyrinthEntry::default()
+        });
+        let (width, height) = cell_count(marked);
+        Cells { marked, width, height }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+    // row by row, starting in the top left corner
+    pub fn all(&self) -> Vec<(usize, usize)> {
+        let width = self.width;
+        (0..self.height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .collect()
+    }
+    pub fn index(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+    pub fn random_cell<R: Rng>(&self, rng: &mut R) -> (usize, usize) {
+        (rng.gen_range(0, self.width), rng.gen_range(0, self.height))
+    }
+    pub fn neighbours(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut result = Vec::with_capacity(4);
+        if x > 0 {
+            result.push((x - 1, y));
+        }
+        if y > 0 {
+            result.push((x, y - 1));
+        }
+        if x + 1 < self.width {
+            result.push((x + 1, y));
+        }
+        if y + 1 < self.height {
+            result.push((x, y + 1));
+        }
+        result
+    }
+    pub fn carve(&mut self, cell: (usize, usize)) {
+        let (x, y) = cell_to_box(cell);
+        self.marked[Dim(x, y)].state = BoxState::Empty;
+    }
+    // removes the wall between two neighbouring cells
+    pub fn connect(&mut self, from: (usize, usize), to: (usize, usize)) {
+        let (from_x, from_y) = cell_to_box(from);
+        let (to_x, to_y) = cell_to_box(to);
+        self.carve(from);
+        self.carve(to);
+        self.marked[Dim((from_x + to_x) / 2, (from_y + to_y) / 2)].state = BoxState::Empty;
+    }
+}
+
+pub fn cell_count(marked: &Array<LabyrinthEntry>) -> (usize, usize) {
+    let (x_box_cnt, y_box_cnt) = marked.dim();
+    (x_box_cnt.saturating_sub(1) / 2, y_box_cnt.saturating_sub(1) / 2)
+}
+
+pub fn cell_to_box((x, y): (usize, usize)) -> (usize, usize) {
+    (2 * x + 1, 2 * y + 1)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum GeneratorKind {
+    #[default]
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    Eller,
+    BinaryTree,
+    Sidewinder,
+}
+
+impl GeneratorKind {
+    pub fn names() -> &'static [&'static str] {
+        &[
+            "backtracker",
+            "prim",
+            "kruskal",
+            "wilson",
+            "eller",
+            "binary-tree",
+            "sidewinder",
+        ]
+    }
+}
+
+impl FromStr for GeneratorKind {
+    type Err = LabyrinthError;
+    fn from_str(name: &str) -> Result<GeneratorKind, LabyrinthError> {
+        match name {
+            "backtracker" => Ok(GeneratorKind::RecursiveBacktracker),
+            "prim" => Ok(GeneratorKind::Prim),
+            "kruskal" => Ok(GeneratorKind::Kruskal),
+            "wilson" => Ok(GeneratorKind::Wilson),
+            "eller" => Ok(GeneratorKind::Eller),
+            "binary-tree" => Ok(GeneratorKind::BinaryTree),
+            "sidewinder" => Ok(GeneratorKind::Sidewinder),
+            _ => Err(LabyrinthError::UnknownGenerator { name: name.to_string() }),
+        }
+    }
+}
+
+impl MazeGenerator for GeneratorKind {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        match *self {
+            GeneratorKind::RecursiveBacktracker => RecursiveBacktracker.generate(marked, rng),
+            GeneratorKind::Prim => Prim.generate(marked, rng),
+            GeneratorKind::Kruskal => Kruskal.generate(marked, rng),
+            GeneratorKind::Wilson => Wilson.generate(marked, rng),
+            GeneratorKind::Eller => Eller.generate(marked, rng),
+            GeneratorKind::BinaryTree => BinaryTree.generate(marked, rng),
+            GeneratorKind::Sidewinder => Sidewinder.generate(marked, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    fn all_kinds() -> Vec<GeneratorKind> {
+        GeneratorKind::names()
+            .iter()
+            .map(|name| name.parse().unwrap())
+            .collect()
+    }
+
+    fn generate(kind: GeneratorKind, x_box_cnt: usize, y_box_cnt: usize, seed: usize) -> Array<LabyrinthEntry> {
+        let mut marked = Array::<LabyrinthEntry>::default(Dim(x_box_cnt, y_box_cnt));
+        let mut rng = StdRng::from_seed(&[seed][..]);
+        kind.generate(&mut marked, &mut rng);
+        marked
+    }
+
+    fn states(marked: &Array<LabyrinthEntry>) -> Vec<BoxState> {
+        marked.iter().map(|entry| entry.state).collect()
+    }
+
+    fn free_boxes(marked: &Array<LabyrinthEntry>) -> usize {
+        marked.iter().filter(|entry| entry.state != BoxState::Labyrinth).count()
+    }
+
+    fn reachable_boxes(marked: &Array<LabyrinthEntry>) -> usize {
+        let mut seen = Array::<bool>::from_elem(marked.dim(), false);
+        let mut stack = vec![(1, 1)];
+        seen[Dim(1, 1)] = true;
+        let mut count = 0;
+        while let Some((x, y)) = stack.pop() {
+            count += 1;
+            for &(next_x, next_y) in &[(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if marked[Dim(next_x, next_y)].state != BoxState::Labyrinth && !seen[Dim(next_x, next_y)] {
+                    seen[Dim(next_x, next_y)] = true;
+                    stack.push((next_x, next_y));
+                }
+            }
+        }
+        count
+    }
+
+    fn assert_perfect(kind: GeneratorKind, x_box_cnt: usize, y_box_cnt: usize, seed: usize) {
+        let marked = generate(kind, x_box_cnt, y_box_cnt, seed);
+        let (width, height) = cell_count(&marked);
+        // a spanning tree over all cells: every cell and one passage less
+        assert_eq!(free_boxes(&marked), 2 * width * height - 1, "{:?}", kind);
+        assert_eq!(reachable_boxes(&marked), free_boxes(&marked), "{:?}", kind);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        assert_eq!(all_kinds().len(), GeneratorKind::names().len());
+        assert!("labyrinth".parse::<GeneratorKind>().is_err());
+    }
+
+    #[test]
+    fn perfect_mazes() {
+        for kind in all_kinds() {
+            for seed in 0..5 {
+                assert_perfect(kind, 21, 15, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn narrow_boards() {
+        for kind in all_kinds() {
+            assert_perfect(kind, 3, 3, 1);
+            assert_perfect(kind, 3, 20, 2);
+            assert_perfect(kind, 20, 3, 3);
+            assert_perfect(kind, 12, 9, 4);
+        }
+    }
+
+    #[test]
+    fn border_stays_wall() {
+        for kind in all_kinds() {
+            let marked = generate(kind, 12, 9, 7);
+            for ((x, y), entry) in marked.indexed_iter() {
+                if x == 0 || y == 0 || x >= 11 || y == 8 || (x % 2 == 0 && y % 2 == 0) {
+                    assert_eq!(entry.state, BoxState::Labyrinth, "{:?}", kind);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        for kind in all_kinds() {
+            let first = generate(kind, 31, 31, 1234);
+            let second = generate(kind, 31, 31, 1234);
+            let other = generate(kind, 31, 31, 4321);
+            assert_eq!(states(&first), states(&second), "{:?}", kind);
+            assert!(states(&first) != states(&other), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn too_small_for_cells() {
+        for kind in all_kinds() {
+            assert_eq!(free_boxes(&generate(kind, 2, 5, 1)), 0);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Randomized Prim: grows the maze from a single cell by opening a random wall
+// on its frontier. Produces many short dead ends around a compact core.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Prim;
+
+impl MazeGenerator for Prim {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        if cells.is_empty() {
+            return;
+        }
+        let mut in_maze = Array::<bool>::from_elem(Dim(cells.width, cells.height), false);
+        let start = cells.random_cell(rng);
+        in_maze[Dim(start.0, start.1)] = true;
+        cells.carve(start);
+        // walls between a cell of the maze and one outside of it
+        let mut frontier: Vec<((usize, usize), (usize, usize))> = cells
+            .neighbours(start)
+            .into_iter()
+            .map(|next| (start, next))
+            .collect();
+        while !frontier.is_empty() {
+            let index = rng.gen_range(0, frontier.len());
+            let (from, to) = frontier.swap_remove(index);
+            if in_maze[Dim(to.0, to.1)] {
+                continue;
+            }
+            in_maze[Dim(to.0, to.1)] = true;
+            cells.connect(from, to);
+            for next in cells.neighbours(to) {
+                if !in_maze[Dim(next.0, next.1)] {
+                    frontier.push((to, next));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use ndarray::Array2 as Array;
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Sidewinder: carves runs of cells to the east, every run is closed by
+// opening the wall to the north in one random cell of it. The top row is a
+// single corridor.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Sidewinder;
+
+impl MazeGenerator for Sidewinder {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        for y in 0..cells.height {
+            let mut run_start = 0;
+            for x in 0..cells.width {
+                cells.carve((x, y));
+                let at_east_border = x + 1 == cells.width;
+                if y > 0 && (at_east_border || rng.gen::<bool>()) {
+                    let north = rng.gen_range(run_start, x + 1);
+                    cells.connect((north, y), (north, y - 1));
+                    run_start = x + 1;
+                } else if !at_east_border {
+                    cells.connect((x, y), (x + 1, y));
+                }
+            }
+        }
+    }
+}
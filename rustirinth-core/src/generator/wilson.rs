@@ -0,0 +1,46 @@
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use rand::Rng;
+use labyrinth::LabyrinthEntry;
+use super::{Cells, MazeGenerator};
+
+// Wilson's algorithm: random walks from every cell outside the maze until
+// they hit the maze, then the walk without its loops is added. Every perfect
+// maze is equally likely, so the result has no bias at all.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Wilson;
+
+impl MazeGenerator for Wilson {
+    fn generate<R: Rng>(&self, marked: &mut Array<LabyrinthEntry>, rng: &mut R) {
+        let mut cells = Cells::new(marked);
+        if cells.is_empty() {
+            return;
+        }
+        let mut in_maze = Array::<bool>::from_elem(Dim(cells.width, cells.height), false);
+        // the direction in which the walk left a cell the last time, so loops
+        // are erased by simply overwriting it
+        let mut next_cell = Array::<Option<(usize, usize)>>::from_elem(Dim(cells.width, cells.height), None);
+        let root = cells.random_cell(rng);
+        in_maze[Dim(root.0, root.1)] = true;
+        cells.carve(root);
+        for start in cells.all() {
+            let mut current = start;
+            while !in_maze[Dim(current.0, current.1)] {
+                let neighbours = cells.neighbours(current);
+                let next = *rng.choose(&neighbours).expect("a cell outside the maze has neighbours");
+                next_cell[Dim(current.0, current.1)] = Some(next);
+                current = next;
+            }
+            current = start;
+            while !in_maze[Dim(current.0, current.1)] {
+                in_maze[Dim(current.0, current.1)] = true;
+                match next_cell[Dim(current.0, current.1)] {
+                    Some(next) => {
+                        cells.connect(current, next);
+                        current = next;
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+}
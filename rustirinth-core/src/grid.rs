@@ -0,0 +1,382 @@
+use std::cmp::min;
+use std::ops::Deref;
+use basic_types::{convert, GeneralRectangle, IsARectangle, IsARectangularArea, LabyrinthError, Rectangle, TwoDimensionalRange};
+use failure::Error;
+
+// Board pixels are the pixels the labyrinth is drawn with, window pixels
+// are where they end up after the view zoomed and panned them; grid
+// coordinates count boxes. The wrappers below make sure that one is never
+// used as another.
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+pub struct GeneralPoint<T>
+where
+    T: Copy + Clone,
+{
+    pub coordinate: (T, T),
+}
+
+pub type Point = GeneralPoint<u32>;
+
+impl<T> GeneralPoint<T>
+where
+    T: Copy + Clone,
+{
+    pub fn x(&self) -> T {
+        self.coordinate.0
+    }
+    pub fn y(&self) -> T {
+        self.coordinate.1
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct GeneralPointInBoardPixels<T>(GeneralPoint<T>)
+where
+    T: Copy + Clone;
+pub type PointInBoardPixels = GeneralPointInBoardPixels<u32>;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct GeneralPointInWindowPixels<T>(GeneralPoint<T>)
+where
+    T: Copy + Clone;
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+pub struct GeneralPointInGridCoordinates<T>(GeneralPoint<T>)
+where
+    T: Copy + Clone;
+pub type PointInGridCoordinates = GeneralPointInGridCoordinates<u32>;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct GeneralRectangleInBoardPixels<T>(GeneralRectangle<T>)
+where
+    T: Copy + Clone + Default + ::std::fmt::Debug;
+pub type RectangleInBoardPixels = GeneralRectangleInBoardPixels<u32>;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct GeneralRectangleInWindowPixels<T>(GeneralRectangle<T>)
+where
+    T: Copy + Clone + Default + ::std::fmt::Debug;
+pub type RectangleInWindowPixels = GeneralRectangleInWindowPixels<u32>;
+
+macro_rules! implement_point {
+    ( $tp:ident ) => (
+        impl<T> $tp<T>
+          where T: Copy + Clone {
+            pub fn new(x: T, y: T) -> $tp<T> {
+                $tp(GeneralPoint { coordinate: (x, y) })
+            }
+        }
+        impl<T> Deref for $tp<T>
+          where T: Copy + Clone {
+            type Target = GeneralPoint<T>;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    )
+}
+
+implement_point!(GeneralPointInBoardPixels);
+implement_point!(GeneralPointInWindowPixels);
+implement_point!(GeneralPointInGridCoordinates);
+
+// the solver and the shapes work with plain tuples
+impl<T> From<(T, T)> for GeneralPointInGridCoordinates<T>
+where
+    T: Copy + Clone,
+{
+    fn from((x, y): (T, T)) -> GeneralPointInGridCoordinates<T> {
+        GeneralPointInGridCoordinates::new(x, y)
+    }
+}
+
+macro_rules! implement_rectangle {
+    ( $tp:ident ) => (
+        impl<T> $tp<T>
+          where T: Copy + Clone + Default + ::std::fmt::Debug {
+            pub fn new(rectangle: GeneralRectangle<T>) -> $tp<T> {
+                $tp(rectangle)
+            }
+        }
+        impl<T> Deref for $tp<T>
+          where T: Copy + Clone + Default + ::std::fmt::Debug {
+            type Target = GeneralRectangle<T>;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+        impl<T> IsARectangle<T> for $tp<T>
+          where T: Copy + Clone + Default + ::std::fmt::Debug {
+            fn from_tuple(tuple: (T, T, T, T)) -> $tp<T> {
+                $tp(GeneralRectangle::from_tuple(tuple))
+            }
+            fn x(&self) -> T {
+                self.0.x()
+            }
+            fn y(&self) -> T {
+                self.0.y()
+            }
+            fn width(&self) -> T {
+                self.0.width()
+            }
+            fn height(&self) -> T {
+                self.0.height()
+            }
+        }
+    )
+}
+
+implement_rectangle!(GeneralRectangleInBoardPixels);
+implement_rectangle!(GeneralRectangleInWindowPixels);
+
+// the boxes of a labyrinth and where they are in board pixels: box (x, y)
+// covers the pixels from area.x + x * box_size to area.x + (x + 1) * box_size,
+// both ends being the one pixel wide lines between the boxes
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Grid {
+    pub area: RectangleInBoardPixels,
+    pub box_size: u32,
+    pub x_box_cnt: u32,
+    pub y_box_cnt: u32,
+}
+
+impl Grid {
+    // at the origin of the board pixels
+    pub fn new(box_size: u32, x_box_cnt: u32, y_box_cnt: u32) -> Result<Grid, Error> {
+        let mut grid = Grid {
+            area: RectangleInBoardPixels::default(),
+            box_size,
+            x_box_cnt,
+            y_box_cnt,
+        };
+        grid.set_box_count(x_box_cnt, y_box_cnt)?;
+        Ok(grid)
+    }
+    // the width or height of box_cnt boxes including the closing line, None
+    // if it does not fit into the pixel coordinates
+    pub fn pixel_size(box_cnt: u32, box_size: u32) -> Option<u32> {
+        box_cnt.checked_mul(box_size).and_then(|pixels| pixels.checked_add(1))
+    }
+    pub fn set_box_size(&mut self, box_size: u32) -> Result<(), Error> {
+        self.update_area_size(box_size, self.x_box_cnt, self.y_box_cnt)
+    }
+    pub fn set_box_count(&mut self, x_box_cnt: u32, y_box_cnt: u32) -> Result<(), Error> {
+        self.update_area_size(self.box_size, x_box_cnt, y_box_cnt)
+    }
+    // leaves the grid unchanged if the new size is too large
+    fn update_area_size(&mut self, box_size: u32, x_box_cnt: u32, y_box_cnt: u32) -> Result<(), Error> {
+        match (Grid::pixel_size(x_box_cnt, box_size), Grid::pixel_size(y_box_cnt, box_size)) {
+            (Some(width), Some(height)) => {
+                self.box_size = box_size;
+                self.x_box_cnt = x_box_cnt;
+                self.y_box_cnt = y_box_cnt;
+                self.area.0.width = width;
+                self.area.0.height = height;
+                Ok(())
+            }
+            _ => Err(LabyrinthError::BoardTooLarge {
+                x_box_cnt,
+                y_box_cnt,
+                box_size,
+            }.into()),
+        }
+    }
+    pub fn move_to(&mut self, point: PointInBoardPixels) {
+        self.area.0.x = point.x();
+        self.area.0.y = point.y();
+    }
+    pub fn is_on_axis(&self, point: PointInBoardPixels) -> bool {
+        self.is_inside(point)
+            && ((point.x() - self.area.top_left_x()) % self.box_size == 0 || (point.y() - self.area.top_left_y()) % self.box_size == 0)
+    }
+    pub fn is_inside(&self, point: PointInBoardPixels) -> bool {
+        point.x() >= self.area.top_left_x() && point.x() < self.area.bottom_right_x() && point.y() >= self.area.top_left_y()
+            && point.y() < self.area.bottom_right_y()
+    }
+    pub fn contains(&self, point: PointInGridCoordinates) -> bool {
+        point.x() < self.x_box_cnt && point.y() < self.y_box_cnt
+    }
+    // the nearest box on the board, None if the board has no boxes
+    pub fn clamp(&self, point: PointInGridCoordinates) -> Option<PointInGridCoordinates> {
+        if self.x_box_cnt == 0 || self.y_box_cnt == 0 {
+            None
+        } else {
+            Some(GeneralPointInGridCoordinates::new(
+                min(point.x(), self.x_box_cnt - 1),
+                min(point.y(), self.y_box_cnt - 1),
+            ))
+        }
+    }
+    // the box next to the given one, None beyond the border of the board
+    pub fn neighbour(&self, point: PointInGridCoordinates, (delta_x, delta_y): (i32, i32)) -> Option<PointInGridCoordinates> {
+        let x = convert(i64::from(point.x()) + i64::from(delta_x)).ok()?;
+        let y = convert(i64::from(point.y()) + i64::from(delta_y)).ok()?;
+        Some(GeneralPointInGridCoordinates::new(x, y)).filter(|&neighbour| self.contains(neighbour))
+    }
+    // the inside of the box without the lines around it, this is what is filled
+    pub fn box_to_pixel(&self, point: PointInGridCoordinates) -> Result<RectangleInBoardPixels, Error> {
+        const BORDER_SIZE: u32 = 1;
+        if point.x() >= self.x_box_cnt || point.y() >= self.y_box_cnt {
+            Err(LabyrinthError::InternalError.into())
+        } else {
+            Ok(GeneralRectangleInBoardPixels(GeneralRectangle {
+                x: self.area.x + self.box_size * point.x() + BORDER_SIZE,
+                y: self.area.y + self.box_size * point.y() + BORDER_SIZE,
+                width: self.box_size - 2 * BORDER_SIZE,
+                height: self.box_size - 2 * BORDER_SIZE,
+            }))
+        }
+    }
+    // all boxes between the two corners including the lines around them,
+    // this is what has to be redrawn when they change
+    pub fn box_range_to_pixel(&self, from: PointInGridCoordinates, to: PointInGridCoordinates) -> RectangleInBoardPixels {
+        let left = min(from.x(), to.x());
+        let top = min(from.y(), to.y());
+        GeneralRectangleInBoardPixels(GeneralRectangle {
+            x: self.area.x + self.box_size * left,
+            y: self.area.y + self.box_size * top,
+            width: self.box_size * (from.x().max(to.x()) - left + 1) + 1,
+            height: self.box_size * (from.y().max(to.y()) - top + 1) + 1,
+        })
+    }
+    pub fn box_center(&self, point: PointInGridCoordinates) -> GeneralPointInBoardPixels<f64> {
+        let half_box = f64::from(self.box_size) / 2.0;
+        GeneralPointInBoardPixels::new(
+            f64::from(self.area.x + self.box_size * point.x()) + half_box,
+            f64::from(self.area.y + self.box_size * point.y()) + half_box,
+        )
+    }
+    // for user input: the lines around the labyrinth do not belong to a box,
+    // a line between two boxes belongs to the right or lower one
+    pub fn pixel_to_box(&self, point: PointInBoardPixels) -> Option<PointInGridCoordinates> {
+        if self.x_box_cnt == 0 || self.y_box_cnt == 0 || point.x() <= self.area.top_left_x() || point.y() <= self.area.top_left_y()
+            || point.x() >= self.area.bottom_right_x() - 1 || point.y() >= self.area.bottom_right_y() - 1
+        {
+            None
+        } else {
+            Some(GeneralPointInGridCoordinates::new(
+                min((point.x() - self.area.x()) / self.box_size, self.x_box_cnt - 1),
+                min((point.y() - self.area.y()) / self.box_size, self.y_box_cnt - 1),
+            ))
+        }
+    }
+    // the boxes which are touched by the rectangle, including those whose
+    // lines are touched only
+    pub fn pixel_area_to_box_range(&self, rectangle: &RectangleInBoardPixels) -> Option<TwoDimensionalRange> {
+        if self.x_box_cnt == 0 || self.y_box_cnt == 0 {
+            return None;
+        }
+        rectangle.intersect(&self.area).map(|intersection| {
+            let range = |start: u32, end: u32, origin: u32, box_cnt: u32| {
+                let first = min((start - origin).saturating_sub(1) / self.box_size, box_cnt - 1);
+                let last = min((end - origin) / self.box_size, box_cnt - 1);
+                (first as usize)..(last as usize + 1)
+            };
+            (
+                range(intersection.top_left_x(), intersection.bottom_right_x(), self.area.x, self.x_box_cnt),
+                range(intersection.top_left_y(), intersection.bottom_right_y(), self.area.y, self.y_box_cnt),
+            )
+        })
+    }
+}
+
+impl From<Rectangle> for RectangleInBoardPixels {
+    fn from(rectangle: Rectangle) -> RectangleInBoardPixels {
+        GeneralRectangleInBoardPixels(rectangle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn grid() -> Grid {
+        let mut grid = Grid::new(10, 4, 3).unwrap();
+        grid.move_to(PointInBoardPixels::new(100, 50));
+        grid
+    }
+
+    #[test]
+    fn area() {
+        let grid = grid();
+        assert_eq!(*grid.area, Rectangle { x: 100, y: 50, width: 41, height: 31 });
+        assert!(grid.is_inside(PointInBoardPixels::new(140, 80)));
+        assert!(!grid.is_inside(PointInBoardPixels::new(141, 80)));
+        assert!(grid.is_on_axis(PointInBoardPixels::new(110, 55)));
+        assert!(!grid.is_on_axis(PointInBoardPixels::new(115, 55)));
+    }
+
+    #[test]
+    fn too_large() {
+        let mut grid = grid();
+        assert!(Grid::new(u32::MAX / 2, 3, 1).is_err());
+        assert!(grid.set_box_size(u32::MAX / 3).is_err());
+        assert!(grid.set_box_count(4, u32::MAX / 5).is_err());
+        // nothing changed
+        assert_eq!(*grid.area, Rectangle { x: 100, y: 50, width: 41, height: 31 });
+        assert_eq!((grid.box_size, grid.x_box_cnt, grid.y_box_cnt), (10, 4, 3));
+        assert_eq!(Grid::pixel_size(2, u32::MAX / 2), Some(u32::MAX));
+        assert_eq!(Grid::pixel_size(3, u32::MAX / 3), None);
+    }
+
+    #[test]
+    fn pixel_to_box() {
+        let grid = grid();
+        assert_eq!(grid.pixel_to_box(PointInBoardPixels::new(100, 55)), None);
+        assert_eq!(
+            grid.pixel_to_box(PointInBoardPixels::new(101, 51)),
+            Some(PointInGridCoordinates::new(0, 0))
+        );
+        assert_eq!(
+            grid.pixel_to_box(PointInBoardPixels::new(110, 79)),
+            Some(PointInGridCoordinates::new(1, 2))
+        );
+        assert_eq!(grid.pixel_to_box(PointInBoardPixels::new(140, 60)), None);
+        assert_eq!(grid.pixel_to_box(PointInBoardPixels::new(120, 80)), None);
+        let center = grid.box_center(PointInGridCoordinates::new(3, 1));
+        assert_eq!(center, GeneralPointInBoardPixels::new(135.0, 65.0));
+        assert_eq!(
+            grid.pixel_to_box(PointInBoardPixels::new(center.x() as u32, center.y() as u32)),
+            Some(PointInGridCoordinates::new(3, 1))
+        );
+    }
+
+    #[test]
+    fn box_to_pixel() {
+        let grid = grid();
+        let inside = grid.box_to_pixel(PointInGridCoordinates::new(1, 2)).unwrap();
+        assert_eq!(*inside, Rectangle { x: 111, y: 71, width: 8, height: 8 });
+        assert!(grid.box_to_pixel(PointInGridCoordinates::new(4, 0)).is_err());
+        let range = grid.box_range_to_pixel(PointInGridCoordinates::new(2, 1), PointInGridCoordinates::new(1, 1));
+        assert_eq!(*range, Rectangle { x: 110, y: 60, width: 21, height: 11 });
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = grid();
+        let corner = PointInGridCoordinates::new(3, 0);
+        assert_eq!(grid.neighbour(corner, (-1, 0)), Some(PointInGridCoordinates::new(2, 0)));
+        assert_eq!(grid.neighbour(corner, (0, 1)), Some(PointInGridCoordinates::new(3, 1)));
+        assert_eq!(grid.neighbour(corner, (1, 0)), None);
+        assert_eq!(grid.neighbour(corner, (0, -1)), None);
+        assert_eq!(grid.clamp(PointInGridCoordinates::new(7, 1)), Some(PointInGridCoordinates::new(3, 1)));
+        assert_eq!(Grid::new(10, 4, 0).unwrap().clamp(PointInGridCoordinates::new(0, 0)), None);
+    }
+
+    #[test]
+    fn pixel_area_to_box_range() {
+        let grid = grid();
+        let everything = RectangleInBoardPixels::from(Rectangle { x: 0, y: 0, width: 500, height: 500 });
+        assert_eq!(grid.pixel_area_to_box_range(&everything), Some((0..4, 0..3)));
+        let inside_one_box = RectangleInBoardPixels::from(Rectangle { x: 112, y: 62, width: 5, height: 5 });
+        assert_eq!(grid.pixel_area_to_box_range(&inside_one_box), Some((1..2, 1..2)));
+        // the line between box 0 and 1 belongs to both
+        let line = RectangleInBoardPixels::from(Rectangle { x: 110, y: 52, width: 0, height: 1 });
+        assert_eq!(grid.pixel_area_to_box_range(&line), Some((0..2, 0..1)));
+        let outside = RectangleInBoardPixels::from(Rectangle { x: 0, y: 0, width: 10, height: 10 });
+        assert_eq!(grid.pixel_area_to_box_range(&outside), None);
+        assert_eq!(Grid::new(10, 0, 3).unwrap().pixel_area_to_box_range(&everything), None);
+    }
+}
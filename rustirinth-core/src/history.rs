@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use labyrinth::BoxState;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BoxChange {
+    pub position: (u32, u32),
+    pub before: BoxState,
+    pub after: BoxState,
+}
+
+// One undo step is a list of box changes, e.g. all boxes painted during a
+// single drag with the mouse. At most `limit` changes are kept, the oldest
+// steps are dropped first.
+#[derive(Debug, Clone)]
+pub struct History {
+    undo: VecDeque<Vec<BoxChange>>,
+    redo: Vec<Vec<BoxChange>>,
+    // record adds to the newest undo step instead of starting a new one
+    grouping: bool,
+    group_started: bool,
+    change_count: usize,
+    limit: usize,
+}
+
+impl History {
+    pub const DEFAULT_LIMIT: usize = 100_000;
+    pub fn new(limit: usize) -> History {
+        History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            grouping: false,
+            group_started: false,
+            change_count: 0,
+            limit,
+        }
+    }
+    // all changes until end_group or undo/redo become a single step
+    pub fn begin_group(&mut self) {
+        self.grouping = true;
+        self.group_started = false;
+    }
+    pub fn end_group(&mut self) {
+        self.grouping = false;
+        self.group_started = false;
+    }
+    pub fn is_grouping(&self) -> bool {
+        self.grouping
+    }
+    pub fn record(&mut self, change: BoxChange) {
+        for step in self.redo.drain(..) {
+            self.change_count -= step.len();
+        }
+        match self.undo.back_mut() {
+            Some(step) if self.group_started => step.push(change),
+            _ => {
+                self.undo.push_back(vec![change]);
+                self.group_started = self.grouping;
+            }
+        }
+        self.change_count += 1;
+        // the newest step is kept in any case, even if it is too large
+        while self.change_count > self.limit && self.undo.len() > 1 {
+            if let Some(step) = self.undo.pop_front() {
+                self.change_count -= step.len();
+            }
+        }
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    // returns the changes of the undone step, which have to be reverted
+    // from the last to the first
+    pub fn undo(&mut self) -> Option<Vec<BoxChange>> {
+        self.end_group();
+        let step = self.undo.pop_back()?;
+        self.redo.push(step.clone());
+        Some(step)
+    }
+    pub fn redo(&mut self) -> Option<Vec<BoxChange>> {
+        self.end_group();
+        let step = self.redo.pop()?;
+        self.undo.push_back(step.clone());
+        Some(step)
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new(History::DEFAULT_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn change(x: u32) -> BoxChange {
+        BoxChange {
+            position: (x, 0),
+            before: BoxState::Empty,
+            after: BoxState::Labyrinth,
+        }
+    }
+
+    #[test]
+    fn single_changes() {
+        let mut history = History::default();
+        history.record(change(1));
+        history.record(change(2));
+        assert_eq!(history.undo(), Some(vec![change(2)]));
+        assert_eq!(history.undo(), Some(vec![change(1)]));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), Some(vec![change(1)]));
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn grouped_changes() {
+        let mut history = History::default();
+        history.begin_group();
+        history.record(change(1));
+        history.record(change(2));
+        history.begin_group();
+        history.record(change(3));
+        assert_eq!(history.undo(), Some(vec![change(3)]));
+        assert_eq!(history.undo(), Some(vec![change(1), change(2)]));
+    }
+
+    #[test]
+    fn recording_clears_redo() {
+        let mut history = History::default();
+        history.record(change(1));
+        history.undo();
+        history.record(change(2));
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn oldest_steps_are_dropped() {
+        let mut history = History::new(3);
+        history.begin_group();
+        history.record(change(1));
+        history.record(change(2));
+        history.end_group();
+        history.record(change(3));
+        history.record(change(4));
+        assert_eq!(history.undo(), Some(vec![change(4)]));
+        assert_eq!(history.undo(), Some(vec![change(3)]));
+        assert!(!history.can_undo());
+    }
+}
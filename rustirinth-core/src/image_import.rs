@@ -0,0 +1,227 @@
+use std::cmp::max;
+use std::collections::BTreeMap;
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use failure::Error;
+use basic_types::LabyrinthError;
+use labyrinth::{BoxState, Labyrinth};
+
+// an image with one rgb triple per pixel, row by row; decoding the image
+// file is left to the caller
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<(u8, u8, u8)>,
+}
+
+impl Bitmap {
+    pub fn new(width: u32, height: u32, pixels: Vec<(u8, u8, u8)>) -> Result<Bitmap, Error> {
+        if pixels.len() != width as usize * height as usize {
+            return Err(LabyrinthError::InternalError.into());
+        }
+        Ok(Bitmap { width, height, pixels })
+    }
+    pub fn pixel(&self, x: u32, y: u32) -> (u8, u8, u8) {
+        self.pixels[y as usize * self.width as usize + x as usize]
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImportOptions {
+    // the number of boxes in a row and in a column, see default_box_count
+    pub box_count: Option<(u32, u32)>,
+    // boxes darker than this (0 black, 1 white) become walls
+    pub threshold: f64,
+    // pure green marks the start, pure red the end
+    pub markers: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> ImportOptions {
+        ImportOptions {
+            box_count: None,
+            threshold: 0.5,
+            markers: true,
+        }
+    }
+}
+
+// one box for every PIXELS_PER_BOX x PIXELS_PER_BOX pixels
+pub fn default_box_count(bitmap: &Bitmap) -> (u32, u32) {
+    const PIXELS_PER_BOX: u32 = 16;
+    (max(bitmap.width / PIXELS_PER_BOX, 1), max(bitmap.height / PIXELS_PER_BOX, 1))
+}
+
+pub fn import(bitmap: &Bitmap, box_size: u32, options: &ImportOptions) -> Result<Labyrinth, Error> {
+    let (x_box_cnt, y_box_cnt) = options.box_count.unwrap_or_else(|| default_box_count(bitmap));
+    if x_box_cnt == 0 || y_box_cnt == 0 {
+        return Err(LabyrinthError::EmptyBoard.into());
+    }
+    if bitmap.width < x_box_cnt || bitmap.height < y_box_cnt {
+        return Err(LabyrinthError::ImageTooSmall {
+            width: bitmap.width,
+            height: bitmap.height,
+            x_box_cnt,
+            y_box_cnt,
+        }.into());
+    }
+    let mut states = Array::<BoxState>::default(Dim(x_box_cnt as usize, y_box_cnt as usize));
+    // the box with the most marker pixels wins, BTreeMap keeps ties deterministic
+    let mut start_votes = BTreeMap::new();
+    let mut end_votes = BTreeMap::new();
+    for y in 0..y_box_cnt {
+        for x in 0..x_box_cnt {
+            // the pixels of a box, the boundaries are spread evenly over the image
+            let left = cell_boundary(x, x_box_cnt, bitmap.width);
+            let right = cell_boundary(x + 1, x_box_cnt, bitmap.width);
+            let top = cell_boundary(y, y_box_cnt, bitmap.height);
+            let bottom = cell_boundary(y + 1, y_box_cnt, bitmap.height);
+            let mut brightness = 0.0;
+            for pixel_y in top..bottom {
+                for pixel_x in left..right {
+                    let pixel = bitmap.pixel(pixel_x, pixel_y);
+                    brightness += luminance(pixel);
+                    if options.markers {
+                        match marker(pixel) {
+                            Some(BoxState::Start) => *start_votes.entry((x, y)).or_insert(0) += 1,
+                            Some(BoxState::End) => *end_votes.entry((x, y)).or_insert(0) += 1,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            brightness /= f64::from((right - left) * (bottom - top));
+            if brightness < options.threshold {
+                states[Dim(x as usize, y as usize)] = BoxState::Labyrinth;
+            }
+        }
+    }
+    let start = most_votes(&start_votes);
+    let end = most_votes(&end_votes).filter(|&end| Some(end) != start);
+    for &(position, state) in [(start, BoxState::Start), (end, BoxState::End)].iter() {
+        if let Some((x, y)) = position {
+            states[Dim(x as usize, y as usize)] = state;
+        }
+    }
+    Labyrinth::from_states(box_size, &states)
+}
+
+fn cell_boundary(index: u32, cell_count: u32, pixel_count: u32) -> u32 {
+    (u64::from(index) * u64::from(pixel_count) / u64::from(cell_count)) as u32
+}
+
+// perceived brightness from 0 (black) to 1 (white)
+fn luminance((red, green, blue): (u8, u8, u8)) -> f64 {
+    (0.299 * f64::from(red) + 0.587 * f64::from(green) + 0.114 * f64::from(blue)) / 255.0
+}
+
+// scanned or drawn markers are seldom exactly 255/0/0, so "pure" allows some noise
+fn marker((red, green, blue): (u8, u8, u8)) -> Option<BoxState> {
+    const STRONG: u8 = 0xc0;
+    const WEAK: u8 = 0x40;
+    if red >= STRONG && green <= WEAK && blue <= WEAK {
+        Some(BoxState::End)
+    } else if green >= STRONG && red <= WEAK && blue <= WEAK {
+        Some(BoxState::Start)
+    } else {
+        None
+    }
+}
+
+fn most_votes(votes: &BTreeMap<(u32, u32), u32>) -> Option<(u32, u32)> {
+    votes
+        .iter()
+        .fold(None, |best: Option<(&(u32, u32), &u32)>, vote| match best {
+            Some(best) if best.1 >= vote.1 => Some(best),
+            _ => Some(vote),
+        })
+        .map(|(&position, _)| position)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use grid::PointInGridCoordinates;
+
+    const WHITE: (u8, u8, u8) = (255, 255, 255);
+    const BLACK: (u8, u8, u8) = (0, 0, 0);
+    const GREEN: (u8, u8, u8) = (10, 230, 20);
+    const RED: (u8, u8, u8) = (250, 0, 30);
+
+    // every character becomes a block of 2x2 pixels
+    fn bitmap(rows: &[&str]) -> Bitmap {
+        let mut pixels = Vec::new();
+        for row in rows {
+            for _ in 0..2 {
+                for character in row.chars() {
+                    let pixel = match character {
+                        '#' => BLACK,
+                        'g' => GREEN,
+                        'r' => RED,
+                        _ => WHITE,
+                    };
+                    pixels.push(pixel);
+                    pixels.push(pixel);
+                }
+            }
+        }
+        Bitmap::new(2 * rows[0].len() as u32, 2 * rows.len() as u32, pixels).unwrap()
+    }
+
+    #[test]
+    fn walls_and_markers() {
+        let image = bitmap(&["#####", "#g.r#", "#####"]);
+        let options = ImportOptions {
+            box_count: Some((5, 3)),
+            ..ImportOptions::default()
+        };
+        let labyrinth = import(&image, 32, &options).unwrap();
+        assert_eq!((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt), (5, 3));
+        assert_eq!(labyrinth.marked[Dim(0, 0)].state, BoxState::Labyrinth);
+        assert_eq!(labyrinth.marked[Dim(2, 1)].state, BoxState::Empty);
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(1, 1)));
+        assert_eq!(labyrinth.end, Some(PointInGridCoordinates::new(3, 1)));
+        assert_eq!(labyrinth.solution.as_ref().unwrap().path.len(), 3);
+        let without_markers = ImportOptions {
+            markers: false,
+            ..options
+        };
+        let labyrinth = import(&image, 32, &without_markers).unwrap();
+        assert_eq!(labyrinth.start, None);
+        assert_eq!(labyrinth.marked[Dim(1, 1)].state, BoxState::Empty);
+    }
+
+    #[test]
+    fn downsampling_and_threshold() {
+        // 8x4 pixels into 2x1 boxes: the left box is 3/4 black, the right one 1/4
+        let image = bitmap(&["###.", "#..."]);
+        let options = ImportOptions {
+            box_count: Some((2, 1)),
+            ..ImportOptions::default()
+        };
+        let labyrinth = import(&image, 16, &options).unwrap();
+        assert_eq!(labyrinth.marked[Dim(0, 0)].state, BoxState::Labyrinth);
+        assert_eq!(labyrinth.marked[Dim(1, 0)].state, BoxState::Empty);
+        let strict = ImportOptions {
+            threshold: 0.8,
+            ..options
+        };
+        let labyrinth = import(&image, 16, &strict).unwrap();
+        assert_eq!(labyrinth.marked[Dim(1, 0)].state, BoxState::Labyrinth);
+    }
+
+    #[test]
+    fn box_count() {
+        let image = Bitmap::new(100, 40, vec![WHITE; 4000]).unwrap();
+        assert_eq!(default_box_count(&image), (6, 2));
+        let labyrinth = import(&image, 16, &ImportOptions::default()).unwrap();
+        assert_eq!((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt), (6, 2));
+        let too_many = ImportOptions {
+            box_count: Some((101, 2)),
+            ..ImportOptions::default()
+        };
+        assert!(import(&image, 16, &too_many).is_err());
+        assert!(Bitmap::new(10, 10, vec![WHITE; 99]).is_err());
+    }
+}
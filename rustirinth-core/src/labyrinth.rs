@@ -0,0 +1,990 @@
+use std::option::Option;
+use std::cmp::{max, min};
+use std::collections::{BTreeSet, HashSet};
+use ndarray::{Array2 as Array, Ix2 as Dim, SliceInfo, SliceOrIndex};
+use basic_types::{convert, Color, IsAColor};
+use grid::{GeneralPointInBoardPixels, GeneralPointInWindowPixels, Grid, PointInBoardPixels, PointInGridCoordinates, RectangleInBoardPixels};
+use solver::{AStar, HeuristicKind, SearchOptions, SearchStep, Solution};
+use failure::Error;
+use rand::Rng;
+use generator::{self, MazeGenerator};
+use history::{BoxChange, History};
+use view::View;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum BoxState {
+    #[default]
+    Empty,
+    Labyrinth,
+    Start,
+    End,
+    Road,
+    Mud,
+    Water,
+}
+
+impl BoxState {
+    pub fn color(&self) -> Color {
+        match *self {
+            BoxState::Empty => Color::get_white(),
+            BoxState::Labyrinth => Color::get_blue(),
+            BoxState::Start => Color::get_green(),
+            BoxState::End => Color::get_red(),
+            BoxState::Road => Color::from_tuple((160.0, 160.0, 160.0)),
+            BoxState::Mud => Color::from_tuple((139.0, 90.0, 43.0)),
+            BoxState::Water => Color::from_tuple((64.0, 164.0, 223.0)),
+        }
+    }
+    // the cost of entering a box, walls cannot be entered at all
+    pub fn cost(&self) -> Option<f64> {
+        match *self {
+            BoxState::Labyrinth => None,
+            BoxState::Empty | BoxState::Start | BoxState::End => Some(1.0),
+            BoxState::Road => Some(0.5),
+            BoxState::Mud => Some(3.0),
+            BoxState::Water => Some(6.0),
+        }
+    }
+}
+
+// how far an animated search got with a box
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Visit {
+    #[default]
+    Unvisited,
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LabyrinthEntry {
+    pub state: BoxState,
+    pub on_path: bool,
+    pub visit: Visit,
+}
+
+impl LabyrinthEntry {
+    pub fn color(&self) -> Color {
+        match (self.state, self.visit) {
+            (BoxState::Start, _) | (BoxState::End, _) => self.state.color(),
+            _ if self.on_path => Color::get_yellow(),
+            (_, Visit::Open) => Color::from_tuple((144.0, 238.0, 144.0)),
+            (_, Visit::Closed) => Color::from_tuple((255.0, 160.0, 122.0)),
+            (state, Visit::Unvisited) => state.color(),
+        }
+    }
+}
+
+// two neighbouring boxes of a path, the smaller one first
+type Segment = (PointInGridCoordinates, PointInGridCoordinates);
+
+#[derive(Debug)]
+enum Animation {
+    Searching(Box<AStar<HeuristicKind>>),
+    Tracing { solution: Solution, traced: usize },
+}
+
+#[derive(Debug)]
+pub struct Labyrinth {
+    pub grid: Grid,
+    pub marked: Array<LabyrinthEntry>,
+    pub start: Option<PointInGridCoordinates>,
+    pub end: Option<PointInGridCoordinates>,
+    pub search_options: SearchOptions,
+    pub solution: Option<Solution>,
+    pub history: History,
+    pub view: View,
+    animation: Option<Animation>,
+}
+
+impl Labyrinth {
+    pub const MINIMUM_BOX_SIZE: u32 = 4;
+    pub fn new(box_size: u32, total_width: u32, total_height: u32) -> Result<Labyrinth, Error> {
+        let (x_box_cnt, y_box_cnt) = Labyrinth::box_count(box_size, total_width, total_height);
+        let mut labyrinth = Labyrinth::with_box_count(box_size, x_box_cnt, y_box_cnt)?;
+        labyrinth.center(total_width, total_height);
+        Ok(labyrinth)
+    }
+    // the number of boxes which fit into the drawing area with a small margin
+    pub fn box_count(box_size: u32, total_width: u32, total_height: u32) -> (u32, u32) {
+        let (width, height) = Labyrinth::usable_size(total_width, total_height);
+        (width / box_size, height / box_size)
+    }
+    // the largest box size with which all boxes fit into the drawing area,
+    // but boxes never get smaller than MINIMUM_BOX_SIZE
+    pub fn fitting_box_size(x_box_cnt: u32, y_box_cnt: u32, total_width: u32, total_height: u32) -> u32 {
+        let (width, height) = Labyrinth::usable_size(total_width, total_height);
+        let box_size = min(width / max(x_box_cnt, 1), height / max(y_box_cnt, 1));
+        max(box_size, Labyrinth::MINIMUM_BOX_SIZE)
+    }
+    fn usable_size(total_width: u32, total_height: u32) -> (u32, u32) {
+        const MARGIN_FACTOR: u32 = 32;
+        let left_margin = total_width / MARGIN_FACTOR;
+        let top_margin = total_height / MARGIN_FACTOR;
+        (total_width - 2 * left_margin, total_height - 2 * top_margin)
+    }
+    pub fn set_box_size(&mut self, box_size: u32) -> Result<(), Error> {
+        self.grid.set_box_size(box_size)
+    }
+    // an empty labyrinth in the top left corner, see center
+    pub fn with_box_count(box_size: u32, x_box_cnt: u32, y_box_cnt: u32) -> Result<Labyrinth, Error> {
+        Ok(Labyrinth {
+            grid: Grid::new(box_size, x_box_cnt, y_box_cnt)?,
+            marked: Array::<LabyrinthEntry>::default(Dim(x_box_cnt as usize, y_box_cnt as usize)),
+            start: None,
+            end: None,
+            search_options: SearchOptions::default(),
+            solution: None,
+            history: History::default(),
+            view: View::default(),
+            animation: None,
+        })
+    }
+    pub fn from_states(box_size: u32, states: &Array<BoxState>) -> Result<Labyrinth, Error> {
+        let (x_box_cnt, y_box_cnt) = states.dim();
+        let mut labyrinth = Labyrinth::with_box_count(box_size, x_box_cnt as u32, y_box_cnt as u32)?;
+        for ((x, y), &state) in states.indexed_iter() {
+            labyrinth.marked[Dim(x, y)].state = state;
+            match state {
+                BoxState::Start => labyrinth.start = Some(PointInGridCoordinates::new(x as u32, y as u32)),
+                BoxState::End => labyrinth.end = Some(PointInGridCoordinates::new(x as u32, y as u32)),
+                _ => {}
+            }
+        }
+        labyrinth.update_path(&mut |_| Ok(()))?;
+        Ok(labyrinth)
+    }
+    // changes the number of boxes, but never removes a box which is not
+    // empty: the labyrinth stays larger than requested in that case
+    pub fn resize<F>(&mut self, x_box_cnt: u32, y_box_cnt: u32, mut call_success: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let (used_x_box_cnt, used_y_box_cnt) = self.used_box_count();
+        let x_box_cnt = max(x_box_cnt, used_x_box_cnt);
+        let y_box_cnt = max(y_box_cnt, used_y_box_cnt);
+        if (x_box_cnt, y_box_cnt) == (self.grid.x_box_cnt, self.grid.y_box_cnt) {
+            return Ok(());
+        }
+        self.grid.set_box_count(x_box_cnt, y_box_cnt)?;
+        let mut marked = Array::<LabyrinthEntry>::default(Dim(x_box_cnt as usize, y_box_cnt as usize));
+        for ((x, y), entry) in self.marked.indexed_iter() {
+            if let Some(new_entry) = marked.get_mut(Dim(x, y)) {
+                new_entry.state = entry.state;
+            }
+        }
+        self.marked = marked;
+        self.update_path(&mut call_success)
+    }
+    // the smallest size which contains all boxes which are not empty
+    fn used_box_count(&self) -> (u32, u32) {
+        self.marked
+            .indexed_iter()
+            .filter(|&(_, entry)| entry.state != BoxState::Empty)
+            .fold((0, 0), |(width, height), ((x, y), _)| {
+                (max(width, x as u32 + 1), max(height, y as u32 + 1))
+            })
+    }
+    pub fn fits_into(&self, total_width: u32, total_height: u32) -> bool {
+        self.grid.area.width <= total_width && self.grid.area.height <= total_height
+    }
+    // moves the labyrinth into the middle of the drawing area
+    pub fn center(&mut self, total_width: u32, total_height: u32) {
+        let area = self.grid.area;
+        self.grid.move_to(PointInBoardPixels::new(
+            (total_width / 2).saturating_sub((area.width - 1) / 2),
+            (total_height / 2).saturating_sub((area.height - 1) / 2),
+        ));
+    }
+    pub fn set_box_state<F>(&mut self, position: GeneralPointInWindowPixels<f64>, state: BoxState, mut call_success: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let clicked_box = self.pixel_to_box(position);
+        if let Some(clicked_box) = clicked_box {
+            // moving start or end changes two boxes, which are undone together
+            let grouping = self.history.is_grouping();
+            if !grouping {
+                self.history.begin_group();
+            }
+            let changed = self.update_marked(clicked_box, state);
+            let previous_endpoint = if changed { self.update_endpoints(clicked_box, state) } else { None };
+            if !grouping {
+                self.history.end_group();
+            }
+            if changed {
+                let mut changed_boxes = vec![clicked_box];
+                changed_boxes.extend(previous_endpoint);
+                for changed_box in changed_boxes {
+                    let rectangle = self.box_to_pixel(changed_box)?;
+                    call_success(&rectangle)?;
+                }
+                self.update_path(&mut call_success)?;
+            }
+        }
+        Ok(())
+    }
+    // sets many boxes at once, e.g. a line or a filled region: they are undone
+    // together and redrawn with a single rectangle around all of them
+    pub fn set_box_states<F>(&mut self, boxes: &[PointInGridCoordinates], state: BoxState, mut call_success: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let grouping = self.history.is_grouping();
+        if !grouping {
+            self.history.begin_group();
+        }
+        let mut bounds: Option<Segment> = None;
+        for &changed_box in boxes {
+            if !self.update_marked(changed_box, state) {
+                continue;
+            }
+            let previous_endpoint = self.update_endpoints(changed_box, state);
+            for &point in Some(changed_box).iter().chain(previous_endpoint.iter()) {
+                bounds = Some(match bounds {
+                    Some((top_left, bottom_right)) => (
+                        PointInGridCoordinates::new(min(top_left.x(), point.x()), min(top_left.y(), point.y())),
+                        PointInGridCoordinates::new(max(bottom_right.x(), point.x()), max(bottom_right.y(), point.y())),
+                    ),
+                    None => (point, point),
+                });
+            }
+        }
+        if !grouping {
+            self.history.end_group();
+        }
+        if let Some(bounds) = bounds {
+            call_success(&self.segment_to_pixel(bounds))?;
+            self.update_path(&mut call_success)?;
+        }
+        Ok(())
+    }
+    // fills the 4-connected region of boxes with the same state as the given one
+    pub fn flood_fill<F>(&mut self, position: PointInGridCoordinates, state: BoxState, call_success: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let region = self.region((position.x(), position.y()));
+        self.set_box_states(&region, state, call_success)
+    }
+    // with an explicit stack instead of recursion, as a region on a large
+    // board can contain millions of boxes
+    fn region(&self, (x, y): (u32, u32)) -> Vec<PointInGridCoordinates> {
+        let region_state = match self.marked.get(Dim(x as usize, y as usize)) {
+            Some(entry) => entry.state,
+            None => return Vec::new(),
+        };
+        let mut visited = Array::<bool>::from_elem(self.marked.dim(), false);
+        visited[Dim(x as usize, y as usize)] = true;
+        let mut stack = vec![(x, y)];
+        let mut region = Vec::new();
+        while let Some((x, y)) = stack.pop() {
+            region.push(PointInGridCoordinates::new(x, y));
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for &(neighbour_x, neighbour_y) in &neighbours {
+                let index = Dim(neighbour_x as usize, neighbour_y as usize);
+                let same_state = self.marked.get(index).map(|entry| entry.state) == Some(region_state);
+                if same_state && !visited[index] {
+                    visited[index] = true;
+                    stack.push((neighbour_x, neighbour_y));
+                }
+            }
+        }
+        region
+    }
+    // start and end are unique on the board: returns the box which lost its
+    // marker because it was placed somewhere else
+    fn update_endpoints(&mut self, changed_box: PointInGridCoordinates, state: BoxState) -> Option<PointInGridCoordinates> {
+        if self.start == Some(changed_box) {
+            self.start = None;
+        }
+        if self.end == Some(changed_box) {
+            self.end = None;
+        }
+        let previous_box = match state {
+            BoxState::Start => self.start.replace(changed_box),
+            BoxState::End => self.end.replace(changed_box),
+            _ => None,
+        };
+        if let Some(previous_box) = previous_box {
+            self.update_marked(previous_box, BoxState::Empty);
+        }
+        previous_box
+    }
+    pub fn set_search_options<F>(&mut self, search_options: SearchOptions, mut call_success: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        self.search_options = search_options;
+        self.update_path(&mut call_success)
+    }
+    fn update_path<F>(&mut self, call_success: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let previous_segments = self.path_segments();
+        self.animation = None;
+        self.solution = match (self.start, self.end) {
+            (Some(start), Some(end)) => self.search_options.solve(&self.marked, start.coordinate, end.coordinate),
+            _ => None,
+        };
+        self.update_highlighting(&previous_segments, call_success)
+    }
+    fn update_highlighting<F>(&mut self, previous_segments: &HashSet<Segment>, call_success: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let segments = self.path_segments();
+        let mut highlighted = Array::<bool>::from_elem(self.marked.dim(), false);
+        for &(x, y) in self.solution.iter().flat_map(|solution| solution.path.iter()) {
+            highlighted[Dim(x as usize, y as usize)] = true;
+        }
+        let mut changed_boxes = Vec::new();
+        for ((x, y), entry) in self.marked.indexed_iter_mut() {
+            if entry.on_path != highlighted[Dim(x, y)] || entry.visit != Visit::Unvisited {
+                entry.on_path = highlighted[Dim(x, y)];
+                entry.visit = Visit::Unvisited;
+                changed_boxes.push((x, y));
+            }
+        }
+        for (x, y) in changed_boxes {
+            let rectangle = self.box_to_pixel(PointInGridCoordinates::new(convert(x)?, convert(y)?))?;
+            call_success(&rectangle)?;
+        }
+        // the line through the path crosses the grid and, for diagonal steps,
+        // the corners of the neighbouring boxes
+        for segment in previous_segments.symmetric_difference(&segments) {
+            call_success(&self.segment_to_pixel(*segment))?;
+        }
+        Ok(())
+    }
+    // replaces the whole board by a random maze with the start in the top left
+    // and the end in the bottom right cell
+    pub fn generate_maze<G, R, F>(&mut self, generator: &G, rng: &mut R, mut call_success: F) -> Result<(), Error>
+    where
+        G: MazeGenerator,
+        R: Rng,
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let previous_states: Vec<BoxState> = self.marked.iter().map(|entry| entry.state).collect();
+        generator.generate(&mut self.marked, rng);
+        self.start = None;
+        self.end = None;
+        let (width, height) = generator::cell_count(&self.marked);
+        if width > 0 && height > 0 {
+            let (start_x, start_y) = generator::cell_to_box((0, 0));
+            let (end_x, end_y) = generator::cell_to_box((width - 1, height - 1));
+            self.marked[Dim(start_x, start_y)].state = BoxState::Start;
+            self.start = Some(PointInGridCoordinates::new(convert(start_x)?, convert(start_y)?));
+            if (end_x, end_y) != (start_x, start_y) {
+                self.marked[Dim(end_x, end_y)].state = BoxState::End;
+                self.end = Some(PointInGridCoordinates::new(convert(end_x)?, convert(end_y)?));
+            }
+        }
+        // the whole maze is a single undo step
+        self.history.begin_group();
+        for (((x, y), entry), &before) in self.marked.indexed_iter().zip(previous_states.iter()) {
+            if entry.state != before {
+                self.history.record(BoxChange {
+                    position: (convert(x)?, convert(y)?),
+                    before,
+                    after: entry.state,
+                });
+            }
+        }
+        self.history.end_group();
+        call_success(&self.grid.area)?;
+        self.update_path(&mut call_success)
+    }
+    // paints during a drag with the mouse are undone together
+    pub fn begin_edit(&mut self) {
+        self.history.begin_group();
+    }
+    // later changes are undone on their own again
+    pub fn end_edit(&mut self) {
+        self.history.end_group();
+    }
+    // returns false if there is nothing to undo
+    pub fn undo<F>(&mut self, mut call_success: F) -> Result<bool, Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        match self.history.undo() {
+            Some(changes) => {
+                let states = changes.iter().rev().map(|change| (change.position, change.before));
+                self.restore(states.collect(), &mut call_success)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    pub fn redo<F>(&mut self, mut call_success: F) -> Result<bool, Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        match self.history.redo() {
+            Some(changes) => {
+                let states = changes.iter().map(|change| (change.position, change.after));
+                self.restore(states.collect(), &mut call_success)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    // sets the boxes in the given order without recording it in the history
+    fn restore<F>(&mut self, states: Vec<((u32, u32), BoxState)>, call_success: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let mut changed_boxes = BTreeSet::new();
+        for ((x, y), state) in states {
+            let position = PointInGridCoordinates::new(x, y);
+            // the board may have become smaller since the change was recorded
+            if let Some(entry) = self.marked.get_mut(Dim(x as usize, y as usize)) {
+                entry.state = state;
+                Labyrinth::restore_endpoint(&mut self.start, BoxState::Start, position, state);
+                Labyrinth::restore_endpoint(&mut self.end, BoxState::End, position, state);
+                changed_boxes.insert(position);
+            }
+        }
+        for changed_box in changed_boxes {
+            let rectangle = self.box_to_pixel(changed_box)?;
+            call_success(&rectangle)?;
+        }
+        self.update_path(call_success)
+    }
+    fn restore_endpoint(
+        endpoint: &mut Option<PointInGridCoordinates>,
+        marker: BoxState,
+        position: PointInGridCoordinates,
+        state: BoxState,
+    ) {
+        if state == marker {
+            *endpoint = Some(position);
+        } else if *endpoint == Some(position) {
+            *endpoint = None;
+        }
+    }
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+    // restarts the search between start and end, which is then carried out
+    // by calling animation_step until it returns false
+    pub fn start_animation<F>(&mut self, mut call_success: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            let previous_segments = self.path_segments();
+            self.animation = None;
+            self.solution = None;
+            self.update_highlighting(&previous_segments, &mut call_success)?;
+            self.animation = Some(Animation::Searching(Box::new(AStar::new(
+                &self.marked,
+                start.coordinate,
+                end.coordinate,
+                self.search_options.heuristic,
+                self.search_options.movement,
+            ))));
+        }
+        Ok(())
+    }
+    pub fn animation_step<F>(&mut self, mut call_success: F) -> Result<bool, Error>
+    where
+        F: FnMut(&RectangleInBoardPixels) -> Result<(), Error>,
+    {
+        let mut changed_boxes = Vec::new();
+        let mut changed_segment = None;
+        self.animation = match self.animation.take() {
+            Some(Animation::Searching(mut search)) => match search.step(&self.marked) {
+                SearchStep::Expanded { closed, opened } => {
+                    for position in opened {
+                        self.update_visit(position, Visit::Open);
+                        changed_boxes.push(position);
+                    }
+                    self.update_visit(closed, Visit::Closed);
+                    changed_boxes.push(closed);
+                    Some(Animation::Searching(search))
+                }
+                SearchStep::Finished(Some(solution)) => Some(Animation::Tracing { solution, traced: 0 }),
+                SearchStep::Finished(None) => None,
+            },
+            Some(Animation::Tracing { solution, traced }) => {
+                let traced = traced + 1;
+                let position = solution.path[traced - 1];
+                self.marked[Dim(position.0 as usize, position.1 as usize)].on_path = true;
+                changed_boxes.push(position);
+                if traced > 1 {
+                    changed_segment = Some((solution.path[traced - 2].into(), position.into()));
+                }
+                if traced == solution.path.len() {
+                    self.solution = Some(solution);
+                    None
+                } else {
+                    Some(Animation::Tracing { solution, traced })
+                }
+            }
+            None => None,
+        };
+        for changed_box in changed_boxes {
+            let rectangle = self.box_to_pixel(changed_box.into())?;
+            call_success(&rectangle)?;
+        }
+        if let Some(segment) = changed_segment {
+            call_success(&self.segment_to_pixel(segment))?;
+        }
+        Ok(self.animation.is_some())
+    }
+    fn update_visit(&mut self, (x, y): (u32, u32), visit: Visit) {
+        if let Some(entry) = self.marked.get_mut(Dim(x as usize, y as usize)) {
+            entry.visit = visit;
+        }
+    }
+    // the part of the path which is currently shown
+    pub fn visible_path(&self) -> &[(u32, u32)] {
+        if let Some(Animation::Tracing { ref solution, traced }) = self.animation {
+            return &solution.path[..traced];
+        }
+        match self.solution {
+            Some(ref solution) => &solution.path,
+            None => &[],
+        }
+    }
+    fn path_segments(&self) -> HashSet<Segment> {
+        self.visible_path()
+            .windows(2)
+            .map(|step| (min(step[0], step[1]).into(), max(step[0], step[1]).into()))
+            .collect()
+    }
+    // the area covered by two neighbouring boxes including their borders
+    pub fn segment_to_pixel(&self, (from, to): Segment) -> RectangleInBoardPixels {
+        self.grid.box_range_to_pixel(from, to)
+    }
+    pub fn box_center(&self, position: PointInGridCoordinates) -> GeneralPointInBoardPixels<f64> {
+        self.grid.box_center(position)
+    }
+    pub fn box_state(&self, position: PointInGridCoordinates) -> Option<BoxState> {
+        self.marked.get(Dim(position.x() as usize, position.y() as usize)).map(|entry| entry.state)
+    }
+    fn update_marked(&mut self, position: PointInGridCoordinates, state: BoxState) -> bool {
+        if let Some(bx) = self.marked.get_mut(Dim(position.x() as usize, position.y() as usize)) {
+            if bx.state != state {
+                self.history.record(BoxChange {
+                    position: position.coordinate,
+                    before: bx.state,
+                    after: state,
+                });
+                bx.state = state;
+                return true;
+            }
+        }
+        false
+    }
+    pub fn call_for_every_box<F>(&self, area: &RectangleInBoardPixels, mut function: F) -> Result<(), Error>
+    where
+        F: FnMut(&RectangleInBoardPixels, &LabyrinthEntry) -> Result<(), Error>,
+    {
+        let (x_range, y_range) = match self.grid.pixel_area_to_box_range(area) {
+            Some(range) => range,
+            None => return Ok(()),
+        };
+        let slice_x = SliceOrIndex::from(x_range.clone());
+        let slice_y = SliceOrIndex::from(y_range.clone());
+        let slice_args = SliceInfo::<[SliceOrIndex; 2], Dim>::new([slice_x, slice_y])?;
+        for ((x_box, y_box), entry) in self.marked.slice(&slice_args).indexed_iter() {
+            let position = PointInGridCoordinates::new(convert(x_box + x_range.start)?, convert(y_box + y_range.start)?);
+            let box_rectangle = self.box_to_pixel(position)?;
+            if let Some(intersection) = box_rectangle.intersect(area) {
+                function(&RectangleInBoardPixels::new(intersection), entry)?;
+            }
+        }
+        Ok(())
+    }
+    // hit testing for a point in the window, undoes zooming and panning
+    pub fn pixel_to_box(&self, position: GeneralPointInWindowPixels<f64>) -> Option<PointInGridCoordinates> {
+        let position = self.view.from_window(position);
+        let (x, y) = (position.x(), position.y());
+        if x < 0.0 || y < 0.0 || x >= f64::from(u32::MAX) || y >= f64::from(u32::MAX) {
+            return None;
+        }
+        self.grid.pixel_to_box(PointInBoardPixels::new(x as u32, y as u32))
+    }
+    // without border, this is just for drawing
+    pub fn box_to_pixel(&self, position: PointInGridCoordinates) -> Result<RectangleInBoardPixels, Error> {
+        self.grid.box_to_pixel(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use basic_types::Rectangle;
+
+    // where a click hits the middle of the box
+    fn click(labyrinth: &Labyrinth, x: u32, y: u32) -> GeneralPointInWindowPixels<f64> {
+        labyrinth.view.to_window(labyrinth.box_center(PointInGridCoordinates::new(x, y)))
+    }
+
+    fn points(positions: &[(u32, u32)]) -> Vec<PointInGridCoordinates> {
+        positions.iter().map(|&position| position.into()).collect()
+    }
+
+    #[test]
+    fn start_is_unique() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let first = click(&labyrinth, 1, 1);
+        let second = click(&labyrinth, 4, 2);
+        labyrinth.set_box_state(first, BoxState::Start, |_| Ok(())).unwrap();
+        let mut redrawn = 0;
+        labyrinth
+            .set_box_state(second, BoxState::Start, |_| {
+                redrawn += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(redrawn, 2);
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(4, 2)));
+        assert_eq!(labyrinth.marked[Dim(1, 1)].state, BoxState::Empty);
+        assert_eq!(labyrinth.marked[Dim(4, 2)].state, BoxState::Start);
+    }
+
+    #[test]
+    fn overwriting_endpoint_removes_it() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let position = click(&labyrinth, 3, 3);
+        labyrinth.set_box_state(position, BoxState::End, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(position, BoxState::Start, |_| Ok(())).unwrap();
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(3, 3)));
+        assert_eq!(labyrinth.end, None);
+        labyrinth.set_box_state(position, BoxState::Labyrinth, |_| Ok(())).unwrap();
+        assert_eq!(labyrinth.start, None);
+    }
+
+    #[test]
+    fn animation_finds_path() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let start = click(&labyrinth, 0, 0);
+        let end = click(&labyrinth, 3, 0);
+        labyrinth.set_box_state(start, BoxState::Start, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(end, BoxState::End, |_| Ok(())).unwrap();
+        let expected = labyrinth.solution.clone();
+        labyrinth.start_animation(|_| Ok(())).unwrap();
+        assert!(labyrinth.is_animating());
+        assert_eq!(labyrinth.solution, None);
+        assert!(!labyrinth.marked[Dim(1, 0)].on_path);
+        labyrinth.animation_step(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(0, 0)].visit, Visit::Closed);
+        assert_eq!(labyrinth.marked[Dim(1, 0)].visit, Visit::Open);
+        let mut steps = 1;
+        while labyrinth.animation_step(|_| Ok(())).unwrap() {
+            steps += 1;
+            assert!(steps < 100);
+        }
+        assert!(!labyrinth.is_animating());
+        assert_eq!(labyrinth.solution, expected);
+        assert!(labyrinth.marked[Dim(1, 0)].on_path);
+        assert_eq!(labyrinth.visible_path().len(), 4);
+    }
+
+    #[test]
+    fn editing_stops_animation() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let start = click(&labyrinth, 0, 0);
+        let end = click(&labyrinth, 3, 3);
+        let wall = click(&labyrinth, 5, 5);
+        labyrinth.set_box_state(start, BoxState::Start, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(end, BoxState::End, |_| Ok(())).unwrap();
+        labyrinth.start_animation(|_| Ok(())).unwrap();
+        labyrinth.animation_step(|_| Ok(())).unwrap();
+        labyrinth.set_box_state(wall, BoxState::Labyrinth, |_| Ok(())).unwrap();
+        assert!(!labyrinth.is_animating());
+        assert!(labyrinth.solution.is_some());
+        assert!(labyrinth.marked.iter().all(|entry| entry.visit == Visit::Unvisited));
+    }
+
+    #[test]
+    fn path_is_highlighted() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let start = click(&labyrinth, 0, 0);
+        let end = click(&labyrinth, 2, 0);
+        labyrinth.set_box_state(start, BoxState::Start, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(end, BoxState::End, |_| Ok(())).unwrap();
+        let solution = labyrinth.solution.clone().unwrap();
+        assert_eq!(solution.path, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(solution.cost, 2.0);
+        assert!(labyrinth.marked[Dim(1, 0)].on_path);
+        assert_eq!(labyrinth.marked[Dim(1, 0)].color(), Color::get_yellow());
+        assert_eq!(labyrinth.marked[Dim(0, 0)].color(), Color::get_green());
+    }
+
+    #[test]
+    fn generated_maze_is_solvable() {
+        use rand::{SeedableRng, StdRng};
+        use generator::GeneratorKind;
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let mut rng = StdRng::from_seed(&[7][..]);
+        labyrinth.generate_maze(&GeneratorKind::Wilson, &mut rng, |_| Ok(())).unwrap();
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(1, 1)));
+        let end = labyrinth.end.unwrap();
+        assert_eq!(labyrinth.marked[Dim(end.x() as usize, end.y() as usize)].state, BoxState::End);
+        assert!(labyrinth.solution.is_some());
+    }
+
+    #[test]
+    fn undo_drag() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        labyrinth.begin_edit();
+        for x in 0..3 {
+            let position = click(&labyrinth, x, 1);
+            labyrinth.set_box_state(position, BoxState::Labyrinth, |_| Ok(())).unwrap();
+        }
+        labyrinth.begin_edit();
+        let position = click(&labyrinth, 5, 5);
+        labyrinth.set_box_state(position, BoxState::Mud, |_| Ok(())).unwrap();
+        assert!(labyrinth.undo(|_| Ok(())).unwrap());
+        assert_eq!(labyrinth.marked[Dim(5, 5)].state, BoxState::Empty);
+        assert_eq!(labyrinth.marked[Dim(2, 1)].state, BoxState::Labyrinth);
+        let mut redrawn = 0;
+        labyrinth
+            .undo(|_| {
+                redrawn += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(redrawn, 3);
+        assert!(labyrinth.marked.iter().all(|entry| entry.state == BoxState::Empty));
+        assert!(!labyrinth.undo(|_| Ok(())).unwrap());
+        assert!(labyrinth.redo(|_| Ok(())).unwrap());
+        assert_eq!(labyrinth.marked[Dim(1, 1)].state, BoxState::Labyrinth);
+    }
+
+    #[test]
+    fn edit_after_stroke_is_own_step() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        labyrinth.begin_edit();
+        for x in 0..3 {
+            let position = click(&labyrinth, x, 1);
+            labyrinth.set_box_state(position, BoxState::Labyrinth, |_| Ok(())).unwrap();
+        }
+        labyrinth.end_edit();
+        labyrinth.set_box_states(&points(&[(5, 5), (6, 5)]), BoxState::Mud, |_| Ok(())).unwrap();
+        let position = click(&labyrinth, 7, 5);
+        labyrinth.set_box_state(position, BoxState::Water, |_| Ok(())).unwrap();
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(7, 5)].state, BoxState::Empty);
+        assert_eq!(labyrinth.marked[Dim(6, 5)].state, BoxState::Mud);
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(5, 5)].state, BoxState::Empty);
+        assert_eq!(labyrinth.marked[Dim(2, 1)].state, BoxState::Labyrinth);
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(2, 1)].state, BoxState::Empty);
+    }
+
+    // a mouse stroke which was not closed, followed by edits with the keyboard
+    fn open_stroke() -> Labyrinth {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        labyrinth.begin_edit();
+        labyrinth.set_box_states(&points(&[(0, 0), (1, 0)]), BoxState::Labyrinth, |_| Ok(())).unwrap();
+        labyrinth
+    }
+
+    #[test]
+    fn every_toggle_is_own_step() {
+        let mut labyrinth = open_stroke();
+        for &state in &[BoxState::Labyrinth, BoxState::Empty, BoxState::Labyrinth] {
+            labyrinth.end_edit();
+            labyrinth.set_box_states(&points(&[(3, 3)]), state, |_| Ok(())).unwrap();
+        }
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(3, 3)].state, BoxState::Empty);
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(3, 3)].state, BoxState::Labyrinth);
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(3, 3)].state, BoxState::Empty);
+        assert_eq!(labyrinth.marked[Dim(1, 0)].state, BoxState::Labyrinth);
+    }
+
+    #[test]
+    fn painting_with_keys_is_one_step() {
+        let mut labyrinth = open_stroke();
+        for run in 0..2 {
+            labyrinth.begin_edit();
+            for x in 0..3 {
+                labyrinth.set_box_states(&points(&[(x, 2 + run), (x + 1, 2 + run)]), BoxState::Mud, |_| Ok(())).unwrap();
+            }
+            labyrinth.end_edit();
+        }
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert!((0..4).all(|x| labyrinth.marked[Dim(x, 3)].state == BoxState::Empty));
+        assert!((0..4).all(|x| labyrinth.marked[Dim(x, 2)].state == BoxState::Mud));
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert!((0..4).all(|x| labyrinth.marked[Dim(x, 2)].state == BoxState::Empty));
+        assert_eq!(labyrinth.marked[Dim(1, 0)].state, BoxState::Labyrinth);
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(1, 0)].state, BoxState::Empty);
+    }
+
+    #[test]
+    fn undo_moved_start() {
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let first = click(&labyrinth, 1, 1);
+        let second = click(&labyrinth, 4, 2);
+        let end = click(&labyrinth, 6, 2);
+        labyrinth.set_box_state(first, BoxState::Start, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(end, BoxState::End, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(second, BoxState::Start, |_| Ok(())).unwrap();
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(1, 1)));
+        assert_eq!(labyrinth.marked[Dim(4, 2)].state, BoxState::Empty);
+        assert_eq!(labyrinth.solution.as_ref().unwrap().path.len(), 7);
+        labyrinth.redo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(4, 2)));
+        assert_eq!(labyrinth.marked[Dim(1, 1)].state, BoxState::Empty);
+    }
+
+    #[test]
+    fn undo_generated_maze() {
+        use rand::{SeedableRng, StdRng};
+        use generator::GeneratorKind;
+        let mut labyrinth = Labyrinth::new(32, 640, 480).unwrap();
+        let mut rng = StdRng::from_seed(&[3][..]);
+        labyrinth.generate_maze(&GeneratorKind::Prim, &mut rng, |_| Ok(())).unwrap();
+        labyrinth.undo(|_| Ok(())).unwrap();
+        assert!(labyrinth.marked.iter().all(|entry| entry.state == BoxState::Empty));
+        assert_eq!((labyrinth.start, labyrinth.end), (None, None));
+        labyrinth.redo(|_| Ok(())).unwrap();
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(1, 1)));
+        assert!(labyrinth.solution.is_some());
+    }
+
+    #[test]
+    fn resize_keeps_boxes() {
+        let mut labyrinth = Labyrinth::with_box_count(32, 10, 8).unwrap();
+        let start = click(&labyrinth, 1, 1);
+        let end = click(&labyrinth, 6, 3);
+        labyrinth.set_box_state(start, BoxState::Start, |_| Ok(())).unwrap();
+        labyrinth.set_box_state(end, BoxState::End, |_| Ok(())).unwrap();
+        labyrinth.resize(20, 12, |_| Ok(())).unwrap();
+        assert_eq!((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt), (20, 12));
+        assert_eq!(labyrinth.grid.area.width, 20 * 32 + 1);
+        assert_eq!(labyrinth.marked[Dim(6, 3)].state, BoxState::End);
+        assert_eq!(labyrinth.marked[Dim(15, 10)].state, BoxState::Empty);
+        // shrinking stops at the last box which is not empty
+        labyrinth.resize(4, 2, |_| Ok(())).unwrap();
+        assert_eq!((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt), (7, 4));
+        assert_eq!(labyrinth.start, Some(PointInGridCoordinates::new(1, 1)));
+        assert_eq!(labyrinth.solution.as_ref().unwrap().path.len(), 8);
+        // too many pixels, the labyrinth stays as it is
+        assert!(labyrinth.resize(u32::MAX / 16, 4, |_| Ok(())).is_err());
+        assert_eq!(labyrinth.marked.dim(), (7, 4));
+        assert_eq!(labyrinth.grid.area.width, 7 * 32 + 1);
+    }
+
+    #[test]
+    fn box_size_fits_window() {
+        // 1920 - 2 * 60 = 1800, 1080 - 2 * 33 = 1014
+        assert_eq!(Labyrinth::fitting_box_size(30, 20, 1920, 1080), 50);
+        assert_eq!(Labyrinth::fitting_box_size(30, 20, 3840, 2160), 101);
+        assert_eq!(Labyrinth::fitting_box_size(1000, 1000, 640, 480), Labyrinth::MINIMUM_BOX_SIZE);
+        let mut labyrinth = Labyrinth::with_box_count(32, 30, 20).unwrap();
+        labyrinth.set_box_size(50).unwrap();
+        assert!(labyrinth.fits_into(1920, 1080));
+        labyrinth.center(1920, 1080);
+        assert_eq!(labyrinth.box_center(PointInGridCoordinates::new(0, 0)), GeneralPointInBoardPixels::new(235.0, 65.0));
+    }
+
+    #[test]
+    fn hit_test_zoomed() {
+        let mut labyrinth = Labyrinth::with_box_count(32, 10, 8).unwrap();
+        assert_eq!(labyrinth.pixel_to_box(GeneralPointInWindowPixels::new(40.0, 70.0)), Some(PointInGridCoordinates::new(1, 2)));
+        labyrinth.view.zoom_at(2.0, GeneralPointInWindowPixels::new(0.0, 0.0));
+        assert_eq!(labyrinth.pixel_to_box(GeneralPointInWindowPixels::new(40.0, 70.0)), Some(PointInGridCoordinates::new(0, 1)));
+        labyrinth.view.pan((-100.0, 0.0));
+        assert_eq!(labyrinth.pixel_to_box(GeneralPointInWindowPixels::new(40.0, 70.0)), Some(PointInGridCoordinates::new(2, 1)));
+        labyrinth.view.pan((200.0, 0.0));
+        assert_eq!(labyrinth.pixel_to_box(GeneralPointInWindowPixels::new(40.0, 70.0)), None);
+        // clicks are in window pixels, the box is found through the view
+        let window = click(&labyrinth, 3, 3);
+        labyrinth.set_box_state(window, BoxState::Labyrinth, |_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(3, 3)].state, BoxState::Labyrinth);
+    }
+
+    #[test]
+    fn set_many_boxes() {
+        let mut labyrinth = Labyrinth::with_box_count(32, 10, 8).unwrap();
+        let mut redrawn = Vec::new();
+        let line = points(&[(1, 2), (2, 2), (3, 3), (30, 30)]);
+        labyrinth
+            .set_box_states(&line, BoxState::Labyrinth, |rectangle| {
+                redrawn.push(*rectangle);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(labyrinth.marked[Dim(3, 3)].state, BoxState::Labyrinth);
+        assert_eq!(
+            redrawn,
+            vec![RectangleInBoardPixels::new(Rectangle {
+                x: 32,
+                y: 64,
+                width: 3 * 32 + 1,
+                height: 2 * 32 + 1,
+            })]
+        );
+        // nothing changes, nothing is redrawn
+        redrawn.clear();
+        labyrinth.set_box_states(&line, BoxState::Labyrinth, |rectangle| {
+            redrawn.push(*rectangle);
+            Ok(())
+        }).unwrap();
+        assert!(redrawn.is_empty());
+        assert!(labyrinth.undo(|_| Ok(())).unwrap());
+        assert!(labyrinth.marked.iter().all(|entry| entry.state == BoxState::Empty));
+    }
+
+    #[test]
+    fn flood_fill_region() {
+        // a wall splits the board into a left and a right part
+        let mut labyrinth = Labyrinth::with_box_count(16, 6, 4).unwrap();
+        let wall: Vec<_> = (0..4).map(|y| PointInGridCoordinates::new(2, y)).collect();
+        labyrinth.set_box_states(&wall, BoxState::Labyrinth, |_| Ok(())).unwrap();
+        let mut redrawn = Vec::new();
+        labyrinth
+            .flood_fill(PointInGridCoordinates::new(0, 3), BoxState::Water, |rectangle| {
+                redrawn.push(*rectangle);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(labyrinth.marked.iter().filter(|entry| entry.state == BoxState::Water).count(), 8);
+        assert_eq!(labyrinth.marked[Dim(3, 0)].state, BoxState::Empty);
+        // only the bounding rectangle of the filled boxes is redrawn
+        assert_eq!(
+            redrawn,
+            vec![RectangleInBoardPixels::new(Rectangle {
+                x: 0,
+                y: 0,
+                width: 2 * 16 + 1,
+                height: 4 * 16 + 1,
+            })]
+        );
+        // filling the wall replaces it at once
+        labyrinth.flood_fill(PointInGridCoordinates::new(2, 1), BoxState::Empty, |_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked.iter().filter(|entry| entry.state == BoxState::Labyrinth).count(), 0);
+        assert!(labyrinth.undo(|_| Ok(())).unwrap());
+        assert_eq!(labyrinth.marked.iter().filter(|entry| entry.state == BoxState::Labyrinth).count(), 4);
+    }
+
+    #[test]
+    fn flood_fill_large_board() {
+        let mut labyrinth = Labyrinth::with_box_count(4, 1000, 1000).unwrap();
+        labyrinth.flood_fill(PointInGridCoordinates::new(500, 500), BoxState::Mud, |_| Ok(())).unwrap();
+        assert!(labyrinth.marked.iter().all(|entry| entry.state == BoxState::Mud));
+        labyrinth.flood_fill(PointInGridCoordinates::new(1000, 0), BoxState::Empty, |_| Ok(())).unwrap();
+        assert_eq!(labyrinth.marked[Dim(0, 0)].state, BoxState::Mud);
+    }
+}
@@ -0,0 +1,20 @@
+// The labyrinth model, its coordinate types and algorithms, without any
+// dependency on gtk, gdk or cairo.
+extern crate conv;
+extern crate failure;
+#[macro_use]
+extern crate failure_derive;
+extern crate ndarray;
+extern crate rand;
+
+pub mod basic_types;
+pub mod grid;
+pub mod labyrinth;
+pub mod solver;
+pub mod generator;
+pub mod file_format;
+pub mod history;
+pub mod image_import;
+pub mod shapes;
+pub mod view;
+pub mod play;
@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+use failure::Error;
+use basic_types::LabyrinthError;
+use grid::PointInGridCoordinates;
+use labyrinth::{BoxState, Labyrinth};
+
+// a walk through the labyrinth, box by box from the start to the end
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub position: PointInGridCoordinates,
+    // the boxes the player has left, a box is repeated when it is left again
+    pub trail: Vec<PointInGridCoordinates>,
+    pub steps: u32,
+    started: Instant,
+    // how long it took to reach the end
+    pub finished: Option<Duration>,
+}
+
+impl Player {
+    pub fn new(labyrinth: &Labyrinth) -> Result<Player, Error> {
+        match (labyrinth.start, labyrinth.end) {
+            (Some(start), Some(_)) => Ok(Player {
+                position: start,
+                trail: Vec::new(),
+                steps: 0,
+                started: Instant::now(),
+                finished: None,
+            }),
+            _ => Err(LabyrinthError::MissingEndpoint.into()),
+        }
+    }
+    // returns the box which was left, None if walls or the edge of the board
+    // are in the way or the end was already reached
+    pub fn step(&mut self, labyrinth: &Labyrinth, direction: (i32, i32)) -> Option<PointInGridCoordinates> {
+        if self.finished.is_some() {
+            return None;
+        }
+        let from = self.position;
+        let to = labyrinth.grid.neighbour(from, direction)?;
+        match labyrinth.box_state(to) {
+            None | Some(BoxState::Labyrinth) => return None,
+            _ => {}
+        }
+        self.trail.push(from);
+        self.position = to;
+        self.steps += 1;
+        if labyrinth.end == Some(to) {
+            self.finished = Some(self.started.elapsed());
+        }
+        Some(from)
+    }
+    pub fn summary(&self) -> Option<String> {
+        self.finished.map(|time| {
+            format!(
+                "Reached the end in {} steps and {}.{} seconds",
+                self.steps,
+                time.as_secs(),
+                time.subsec_millis() / 100
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::io::Cursor;
+    use file_format;
+
+    fn labyrinth(rows: &str) -> Labyrinth {
+        let text = format!("rustirinth 1\nbox-size 16\nsize 3 2\n{}", rows);
+        file_format::read(Cursor::new(text.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn walk_to_the_end() {
+        let labyrinth = labyrinth("S#E\n...\n");
+        let mut player = Player::new(&labyrinth).unwrap();
+        assert_eq!(player.step(&labyrinth, (1, 0)), None);
+        assert_eq!(player.step(&labyrinth, (-1, 0)), None);
+        assert_eq!(player.step(&labyrinth, (0, 1)), Some(PointInGridCoordinates::new(0, 0)));
+        assert_eq!(player.step(&labyrinth, (1, 0)), Some(PointInGridCoordinates::new(0, 1)));
+        assert_eq!(player.step(&labyrinth, (1, 0)), Some(PointInGridCoordinates::new(1, 1)));
+        assert_eq!(player.summary(), None);
+        assert_eq!(player.step(&labyrinth, (0, -1)), Some(PointInGridCoordinates::new(2, 1)));
+        assert_eq!(player.position, PointInGridCoordinates::new(2, 0));
+        let trail: Vec<_> = player.trail.iter().map(|point| point.coordinate).collect();
+        assert_eq!(trail, vec![(0, 0), (0, 1), (1, 1), (2, 1)]);
+        assert!(player.summary().unwrap().starts_with("Reached the end in 4 steps"));
+        // the walk is over
+        assert_eq!(player.step(&labyrinth, (0, 1)), None);
+    }
+
+    #[test]
+    fn needs_start_and_end() {
+        assert!(Player::new(&labyrinth("S..\n...\n")).is_err());
+    }
+}
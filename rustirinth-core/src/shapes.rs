@@ -0,0 +1,88 @@
+use std::cmp::{max, min};
+
+// shapes which are drawn by dragging from one box to another
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Shape {
+    Line,
+    Rectangle,
+    FilledRectangle,
+}
+
+impl Shape {
+    // the boxes covered by the shape between the two boxes, every box once
+    pub fn boxes(&self, from: (u32, u32), to: (u32, u32)) -> Vec<(u32, u32)> {
+        match *self {
+            Shape::Line => line(from, to),
+            Shape::Rectangle => rectangle(from, to, false),
+            Shape::FilledRectangle => rectangle(from, to, true),
+        }
+    }
+}
+
+// Bresenham's algorithm, the boxes are 8-connected
+fn line((from_x, from_y): (u32, u32), (to_x, to_y): (u32, u32)) -> Vec<(u32, u32)> {
+    let (mut x, mut y) = (i64::from(from_x), i64::from(from_y));
+    let (to_x, to_y) = (i64::from(to_x), i64::from(to_y));
+    let delta_x = (to_x - x).abs();
+    let delta_y = -(to_y - y).abs();
+    let step_x = if x < to_x { 1 } else { -1 };
+    let step_y = if y < to_y { 1 } else { -1 };
+    let mut error = delta_x + delta_y;
+    let mut boxes = Vec::new();
+    loop {
+        boxes.push((x as u32, y as u32));
+        if x == to_x && y == to_y {
+            return boxes;
+        }
+        let doubled_error = 2 * error;
+        if doubled_error >= delta_y {
+            error += delta_y;
+            x += step_x;
+        }
+        if doubled_error <= delta_x {
+            error += delta_x;
+            y += step_y;
+        }
+    }
+}
+
+fn rectangle((from_x, from_y): (u32, u32), (to_x, to_y): (u32, u32), filled: bool) -> Vec<(u32, u32)> {
+    let (left, right) = (min(from_x, to_x), max(from_x, to_x));
+    let (top, bottom) = (min(from_y, to_y), max(from_y, to_y));
+    let mut boxes = Vec::new();
+    for y in top..bottom + 1 {
+        for x in left..right + 1 {
+            if filled || x == left || x == right || y == top || y == bottom {
+                boxes.push((x, y));
+            }
+        }
+    }
+    boxes
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn lines() {
+        assert_eq!(Shape::Line.boxes((2, 3), (2, 3)), vec![(2, 3)]);
+        assert_eq!(Shape::Line.boxes((0, 0), (3, 0)), vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(Shape::Line.boxes((3, 3), (0, 0)), vec![(3, 3), (2, 2), (1, 1), (0, 0)]);
+        assert_eq!(Shape::Line.boxes((0, 0), (4, 2)), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+        assert_eq!(Shape::Line.boxes((1, 4), (0, 0)).len(), 5);
+    }
+
+    #[test]
+    fn rectangles() {
+        let mut hollow = Shape::Rectangle.boxes((3, 2), (0, 0));
+        hollow.sort();
+        assert_eq!(
+            hollow,
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 2), (3, 0), (3, 1), (3, 2)]
+        );
+        assert_eq!(Shape::FilledRectangle.boxes((0, 0), (3, 2)).len(), 12);
+        assert_eq!(Shape::Rectangle.boxes((1, 1), (1, 4)).len(), 4);
+    }
+}
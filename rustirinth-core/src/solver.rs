@@ -0,0 +1,737 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::str::FromStr;
+use ndarray::{Array2 as Array, Ix2 as Dim};
+use basic_types::LabyrinthError;
+use labyrinth::LabyrinthEntry;
+
+pub type Path = Vec<(u32, u32)>;
+
+pub trait Heuristic {
+    // must never overestimate the remaining cost, otherwise the path found is
+    // not necessarily the shortest one
+    fn estimate(&self, from: (u32, u32), to: (u32, u32)) -> f64;
+    // the estimate when moving in the given neighbourhood, for heuristics which
+    // would overestimate with diagonal steps
+    fn estimate_for(&self, from: (u32, u32), to: (u32, u32), _neighbourhood: Neighbourhood) -> f64 {
+        self.estimate(from, to)
+    }
+}
+
+fn distances((from_x, from_y): (u32, u32), (to_x, to_y): (u32, u32)) -> (f64, f64) {
+    (
+        (f64::from(from_x) - f64::from(to_x)).abs(),
+        (f64::from(from_y) - f64::from(to_y)).abs(),
+    )
+}
+
+impl<H> Heuristic for &H
+where
+    H: Heuristic + ?Sized,
+{
+    fn estimate(&self, from: (u32, u32), to: (u32, u32)) -> f64 {
+        (**self).estimate(from, to)
+    }
+    fn estimate_for(&self, from: (u32, u32), to: (u32, u32), neighbourhood: Neighbourhood) -> f64 {
+        (**self).estimate_for(from, to, neighbourhood)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Manhattan;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, from: (u32, u32), to: (u32, u32)) -> f64 {
+        let (dx, dy) = distances(from, to);
+        dx + dy
+    }
+    // a diagonal step covers one box in both directions for sqrt(2), so with
+    // diagonal steps the octile distance replaces the Manhattan distance
+    fn estimate_for(&self, from: (u32, u32), to: (u32, u32), neighbourhood: Neighbourhood) -> f64 {
+        match neighbourhood {
+            Neighbourhood::Four => self.estimate(from, to),
+            Neighbourhood::Eight => {
+                let (dx, dy) = distances(from, to);
+                dx.max(dy) + (::std::f64::consts::SQRT_2 - 1.0) * dx.min(dy)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Euclidean;
+
+impl Heuristic for Euclidean {
+    fn estimate(&self, from: (u32, u32), to: (u32, u32)) -> f64 {
+        let (dx, dy) = distances(from, to);
+        dx.hypot(dy)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Chebyshev;
+
+impl Heuristic for Chebyshev {
+    fn estimate(&self, from: (u32, u32), to: (u32, u32)) -> f64 {
+        let (dx, dy) = distances(from, to);
+        dx.max(dy)
+    }
+}
+
+// without any estimate A* degenerates to Dijkstra's algorithm
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Zero;
+
+impl Heuristic for Zero {
+    fn estimate(&self, _: (u32, u32), _: (u32, u32)) -> f64 {
+        0.0
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum HeuristicKind {
+    #[default]
+    Manhattan,
+    Euclidean,
+    Chebyshev,
+    Zero,
+}
+
+impl HeuristicKind {
+    pub fn names() -> &'static [&'static str] {
+        &["manhattan", "euclidean", "chebyshev", "dijkstra"]
+    }
+}
+
+impl FromStr for HeuristicKind {
+    type Err = LabyrinthError;
+    fn from_str(name: &str) -> Result<HeuristicKind, LabyrinthError> {
+        match name {
+            "manhattan" => Ok(HeuristicKind::Manhattan),
+            "euclidean" => Ok(HeuristicKind::Euclidean),
+            "chebyshev" => Ok(HeuristicKind::Chebyshev),
+            "dijkstra" | "zero" => Ok(HeuristicKind::Zero),
+            _ => Err(LabyrinthError::UnknownHeuristic { name: name.to_string() }),
+        }
+    }
+}
+
+impl Heuristic for HeuristicKind {
+    fn estimate(&self, from: (u32, u32), to: (u32, u32)) -> f64 {
+        match *self {
+            HeuristicKind::Manhattan => Manhattan.estimate(from, to),
+            HeuristicKind::Euclidean => Euclidean.estimate(from, to),
+            HeuristicKind::Chebyshev => Chebyshev.estimate(from, to),
+            HeuristicKind::Zero => Zero.estimate(from, to),
+        }
+    }
+    fn estimate_for(&self, from: (u32, u32), to: (u32, u32), neighbourhood: Neighbourhood) -> f64 {
+        match *self {
+            HeuristicKind::Manhattan => Manhattan.estimate_for(from, to, neighbourhood),
+            HeuristicKind::Euclidean => Euclidean.estimate_for(from, to, neighbourhood),
+            HeuristicKind::Chebyshev => Chebyshev.estimate_for(from, to, neighbourhood),
+            HeuristicKind::Zero => Zero.estimate_for(from, to, neighbourhood),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Neighbourhood {
+    #[default]
+    Four,
+    Eight,
+}
+
+impl Neighbourhood {
+    pub fn names() -> &'static [&'static str] {
+        &["4", "8"]
+    }
+    pub fn toggled(&self) -> Neighbourhood {
+        match *self {
+            Neighbourhood::Four => Neighbourhood::Eight,
+            Neighbourhood::Eight => Neighbourhood::Four,
+        }
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = LabyrinthError;
+    fn from_str(name: &str) -> Result<Neighbourhood, LabyrinthError> {
+        match name {
+            "4" => Ok(Neighbourhood::Four),
+            "8" => Ok(Neighbourhood::Eight),
+            _ => Err(LabyrinthError::UnknownNeighbourhood { name: name.to_string() }),
+        }
+    }
+}
+
+// decides whether a diagonal step may pass the corner of a wall
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum CornerCutting {
+    // always, even between two diagonally touching walls
+    Allowed,
+    // only if at most one of the two orthogonally adjacent boxes is a wall
+    #[default]
+    NoSqueezing,
+    // only if none of the two orthogonally adjacent boxes is a wall
+    Forbidden,
+}
+
+impl CornerCutting {
+    pub fn names() -> &'static [&'static str] {
+        &["allowed", "no-squeezing", "forbidden"]
+    }
+}
+
+impl FromStr for CornerCutting {
+    type Err = LabyrinthError;
+    fn from_str(name: &str) -> Result<CornerCutting, LabyrinthError> {
+        match name {
+            "allowed" => Ok(CornerCutting::Allowed),
+            "no-squeezing" => Ok(CornerCutting::NoSqueezing),
+            "forbidden" => Ok(CornerCutting::Forbidden),
+            _ => Err(LabyrinthError::UnknownCornerCutting { name: name.to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Movement {
+    pub neighbourhood: Neighbourhood,
+    pub corner_cutting: CornerCutting,
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct SearchOptions {
+    pub heuristic: HeuristicKind,
+    pub movement: Movement,
+}
+
+impl SearchOptions {
+    pub fn solve(&self, marked: &Array<LabyrinthEntry>, start: (u32, u32), end: (u32, u32)) -> Option<Solution> {
+        a_star(marked, start, end, &self.heuristic, self.movement)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub path: Path,
+    pub cost: f64,
+    pub expanded: usize,
+}
+
+impl Solution {
+    pub fn summary(&self) -> String {
+        format!("Length {}, cost {}, {} boxes expanded", self.path.len() - 1, self.cost, self.expanded)
+    }
+}
+
+pub fn step_cost(marked: &Array<LabyrinthEntry>, (x, y): (u32, u32)) -> Option<f64> {
+    marked.get(Dim(x as usize, y as usize)).and_then(|entry| entry.state.cost())
+}
+
+pub fn is_passable(marked: &Array<LabyrinthEntry>, position: (u32, u32)) -> bool {
+    step_cost(marked, position).is_some()
+}
+
+// the heuristics estimate in steps, scaling them with the cheapest box on the
+// board keeps them admissible if there are boxes cheaper than an empty one
+fn minimum_step_cost(marked: &Array<LabyrinthEntry>) -> f64 {
+    marked
+        .iter()
+        .filter_map(|entry| entry.state.cost())
+        .fold(f64::INFINITY, f64::min)
+}
+
+// the reachable neighbours together with the factor for the step length
+fn neighbours(marked: &Array<LabyrinthEntry>, (x, y): (u32, u32), movement: Movement) -> Vec<((u32, u32), f64)> {
+    const ORTHOGONAL: [(i64, i64); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+    const DIAGONAL: [(i64, i64); 4] = [(-1, -1), (1, -1), (1, 1), (-1, 1)];
+    let (x_box_cnt, y_box_cnt) = marked.dim();
+    let shifted = |(dx, dy): (i64, i64)| -> Option<(u32, u32)> {
+        let next_x = i64::from(x) + dx;
+        let next_y = i64::from(y) + dy;
+        if next_x < 0 || next_y < 0 || next_x >= x_box_cnt as i64 || next_y >= y_box_cnt as i64 {
+            None
+        } else {
+            Some((next_x as u32, next_y as u32))
+        }
+    };
+    let mut result: Vec<((u32, u32), f64)> = ORTHOGONAL
+        .iter()
+        .filter_map(|&direction| shifted(direction))
+        .map(|next| (next, 1.0))
+        .collect();
+    if movement.neighbourhood == Neighbourhood::Eight {
+        for &(dx, dy) in &DIAGONAL {
+            if let Some(next) = shifted((dx, dy)) {
+                // both orthogonally adjacent boxes exist if the diagonal one does
+                let blocked_x = !is_passable(marked, (next.0, y));
+                let blocked_y = !is_passable(marked, (x, next.1));
+                let allowed = match movement.corner_cutting {
+                    CornerCutting::Allowed => true,
+                    CornerCutting::NoSqueezing => !(blocked_x && blocked_y),
+                    CornerCutting::Forbidden => !(blocked_x || blocked_y),
+                };
+                if allowed {
+                    result.push((next, ::std::f64::consts::SQRT_2));
+                }
+            }
+        }
+    }
+    result
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct OpenEntry {
+    estimate: f64,
+    cost: f64,
+    position: (u32, u32),
+}
+
+impl Eq for OpenEntry {}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &OpenEntry) -> Ordering {
+        // BinaryHeap is a max-heap: the smallest estimate has to compare greatest,
+        // on ties the entry which got further wins
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.cost.partial_cmp(&other.cost).unwrap_or(Ordering::Equal))
+    }
+}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &OpenEntry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchStep {
+    // a box was taken from the open set and closed, its neighbours which were
+    // reached more cheaply than before were (re-)added to the open set
+    Expanded { closed: (u32, u32), opened: Vec<(u32, u32)> },
+    Finished(Option<Solution>),
+}
+
+// A* over the 4- or 8-connected boxes minimising the summed cost of all entered
+// boxes, walls are never entered. The search can be carried out step by step,
+// the board must not change in between.
+#[derive(Debug, Clone)]
+pub struct AStar<H>
+where
+    H: Heuristic,
+{
+    start: (u32, u32),
+    end: (u32, u32),
+    heuristic: H,
+    movement: Movement,
+    scale: f64,
+    cost: Array<f64>,
+    predecessor: Array<Option<(u32, u32)>>,
+    closed: Array<bool>,
+    open: BinaryHeap<OpenEntry>,
+    expanded: usize,
+    result: Option<Option<Solution>>,
+}
+
+impl<H> AStar<H>
+where
+    H: Heuristic,
+{
+    pub fn new(marked: &Array<LabyrinthEntry>, start: (u32, u32), end: (u32, u32), heuristic: H, movement: Movement) -> AStar<H> {
+        let scale = minimum_step_cost(marked);
+        let mut search = AStar {
+            start,
+            end,
+            heuristic,
+            movement,
+            scale,
+            cost: Array::<f64>::from_elem(marked.dim(), f64::INFINITY),
+            predecessor: Array::<Option<(u32, u32)>>::from_elem(marked.dim(), None),
+            closed: Array::<bool>::from_elem(marked.dim(), false),
+            open: BinaryHeap::new(),
+            expanded: 0,
+            result: None,
+        };
+        if is_passable(marked, start) && is_passable(marked, end) {
+            search.cost[index(start)] = 0.0;
+            search.open.push(OpenEntry {
+                estimate: scale * search.heuristic.estimate_for(start, end, movement.neighbourhood),
+                cost: 0.0,
+                position: start,
+            });
+        } else {
+            search.result = Some(None);
+        }
+        search
+    }
+    pub fn step(&mut self, marked: &Array<LabyrinthEntry>) -> SearchStep {
+        if let Some(ref result) = self.result {
+            return SearchStep::Finished(result.clone());
+        }
+        while let Some(current) = self.open.pop() {
+            if self.closed[index(current.position)] {
+                // stale entry, the box was reached more cheaply in the meantime
+                continue;
+            }
+            self.closed[index(current.position)] = true;
+            self.expanded += 1;
+            if current.position == self.end {
+                let solution = Solution {
+                    path: trace_back(&self.predecessor, self.start, self.end),
+                    cost: current.cost,
+                    expanded: self.expanded,
+                };
+                self.result = Some(Some(solution.clone()));
+                return SearchStep::Finished(Some(solution));
+            }
+            let opened = self.expand(marked, current);
+            return SearchStep::Expanded {
+                closed: current.position,
+                opened,
+            };
+        }
+        self.result = Some(None);
+        SearchStep::Finished(None)
+    }
+    pub fn run(&mut self, marked: &Array<LabyrinthEntry>) -> Option<Solution> {
+        loop {
+            if let SearchStep::Finished(result) = self.step(marked) {
+                return result;
+            }
+        }
+    }
+    fn expand(&mut self, marked: &Array<LabyrinthEntry>, current: OpenEntry) -> Vec<(u32, u32)> {
+        let mut opened = Vec::new();
+        for (next, length) in neighbours(marked, current.position, self.movement) {
+            let step = match step_cost(marked, next) {
+                Some(step) if !self.closed[index(next)] => length * step,
+                _ => continue,
+            };
+            let next_cost = current.cost + step;
+            if next_cost < self.cost[index(next)] {
+                self.cost[index(next)] = next_cost;
+                self.predecessor[index(next)] = Some(current.position);
+                self.open.push(OpenEntry {
+                    estimate: next_cost + self.scale * self.heuristic.estimate_for(next, self.end, self.movement.neighbourhood),
+                    cost: next_cost,
+                    position: next,
+                });
+                opened.push(next);
+            }
+        }
+        opened
+    }
+}
+
+// Returns the boxes from start to end (both inclusive) or None if the end
+// cannot be reached.
+pub fn a_star<H>(
+    marked: &Array<LabyrinthEntry>,
+    start: (u32, u32),
+    end: (u32, u32),
+    heuristic: &H,
+    movement: Movement,
+) -> Option<Solution>
+where
+    H: Heuristic + ?Sized,
+{
+    AStar::new(marked, start, end, heuristic, movement).run(marked)
+}
+
+fn index((x, y): (u32, u32)) -> Dim {
+    Dim(x as usize, y as usize)
+}
+
+fn trace_back(predecessor: &Array<Option<(u32, u32)>>, start: (u32, u32), end: (u32, u32)) -> Path {
+    let mut path = vec![end];
+    let mut current = end;
+    while current != start {
+        current = predecessor[index(current)].expect("every reached box has a predecessor");
+        path.push(current);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use labyrinth::BoxState;
+
+    fn board(rows: &[&str]) -> Array<LabyrinthEntry> {
+        let mut marked = Array::<LabyrinthEntry>::default(Dim(rows[0].len(), rows.len()));
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                marked[Dim(x, y)].state = match cell {
+                    '#' => BoxState::Labyrinth,
+                    '=' => BoxState::Road,
+                    '%' => BoxState::Mud,
+                    '~' => BoxState::Water,
+                    _ => BoxState::Empty,
+                };
+            }
+        }
+        marked
+    }
+
+    fn assert_connected(marked: &Array<LabyrinthEntry>, path: &Path) {
+        for step in path.windows(2) {
+            let (a, b) = (step[0], step[1]);
+            let distance = (i64::from(a.0) - i64::from(b.0)).abs() + (i64::from(a.1) - i64::from(b.1)).abs();
+            assert_eq!(distance, 1);
+            assert!(is_passable(marked, b));
+        }
+    }
+
+    const HEURISTICS: [HeuristicKind; 4] = [
+        HeuristicKind::Manhattan,
+        HeuristicKind::Euclidean,
+        HeuristicKind::Chebyshev,
+        HeuristicKind::Zero,
+    ];
+
+    #[test]
+    fn straight_line() {
+        let marked = board(&["....", "....", "...."]);
+        let solution = a_star(&marked, (0, 1), (3, 1), &Manhattan, Movement::default()).unwrap();
+        assert_eq!(solution.path, vec![(0, 1), (1, 1), (2, 1), (3, 1)]);
+        assert_eq!(solution.cost, 3.0);
+        assert_eq!(solution.expanded, 4);
+        assert_eq!(solution.summary(), "Length 3, cost 3, 4 boxes expanded");
+    }
+
+    #[test]
+    fn start_equals_end() {
+        let marked = board(&["..", ".."]);
+        let solution = a_star(&marked, (1, 1), (1, 1), &Zero, Movement::default()).unwrap();
+        assert_eq!(solution.path, vec![(1, 1)]);
+        assert_eq!(solution.cost, 0.0);
+        assert_eq!(solution.expanded, 1);
+    }
+
+    #[test]
+    fn walks_around_walls() {
+        let marked = board(&[".#...", ".#.#.", "...#."]);
+        for heuristic in &HEURISTICS {
+            let solution = a_star(&marked, (0, 0), (4, 0), heuristic, Movement::default()).unwrap();
+            assert_eq!(solution.path.len(), 9);
+            assert_eq!(solution.cost, 8.0);
+            assert_eq!(solution.path.first(), Some(&(0, 0)));
+            assert_eq!(solution.path.last(), Some(&(4, 0)));
+            assert_connected(&marked, &solution.path);
+        }
+    }
+
+    #[test]
+    fn heuristics_agree_on_cost() {
+        let marked = board(&[
+            "..........",
+            ".########.",
+            ".#......#.",
+            ".#...##.#.",
+            "...#..#...",
+            "####..####",
+            "..........",
+        ]);
+        for heuristic in &HEURISTICS {
+            let solution = a_star(&marked, (0, 0), (4, 4), heuristic, Movement::default()).unwrap();
+            assert_eq!(solution.cost, 10.0);
+            assert_connected(&marked, &solution.path);
+        }
+    }
+
+    #[test]
+    fn informed_search_expands_less() {
+        let marked = board(&["..........", "..........", "..........", "..........", ".........."]);
+        let informed = a_star(&marked, (0, 2), (9, 2), &Manhattan, Movement::default()).unwrap();
+        let uninformed = a_star(&marked, (0, 2), (9, 2), &Zero, Movement::default()).unwrap();
+        assert_eq!(informed.cost, uninformed.cost);
+        assert!(informed.expanded < uninformed.expanded);
+    }
+
+    #[test]
+    fn avoids_expensive_terrain() {
+        let marked = board(&["...", ".~.", ".~.", "..."]);
+        for heuristic in &HEURISTICS {
+            let solution = a_star(&marked, (1, 0), (1, 3), heuristic, Movement::default()).unwrap();
+            assert_eq!(solution.cost, 5.0);
+            assert_eq!(solution.path.len(), 6);
+            assert_connected(&marked, &solution.path);
+        }
+    }
+
+    #[test]
+    fn crosses_terrain_if_cheaper() {
+        let marked = board(&["....", "#%##", "...."]);
+        let solution = a_star(&marked, (0, 0), (0, 2), &Manhattan, Movement::default()).unwrap();
+        assert_eq!(solution.path, vec![(0, 0), (1, 0), (1, 1), (1, 2), (0, 2)]);
+        assert_eq!(solution.cost, 6.0);
+    }
+
+    #[test]
+    fn prefers_roads() {
+        let marked = board(&["=====", "=...=", "=...=", "....."]);
+        for heuristic in &HEURISTICS {
+            let solution = a_star(&marked, (0, 1), (4, 1), heuristic, Movement::default()).unwrap();
+            assert_eq!(solution.cost, 3.0);
+            assert!(solution.path.contains(&(2, 0)));
+        }
+    }
+
+    fn eight_connected(corner_cutting: CornerCutting) -> Movement {
+        Movement {
+            neighbourhood: Neighbourhood::Eight,
+            corner_cutting,
+        }
+    }
+
+    #[test]
+    fn diagonal_shortcut() {
+        let marked = board(&[".....", ".....", ".....", ".....", "....."]);
+        for heuristic in &HEURISTICS {
+            let solution = a_star(&marked, (0, 0), (4, 4), heuristic, eight_connected(CornerCutting::Allowed)).unwrap();
+            assert_eq!(solution.path, vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+            assert!((solution.cost - 4.0 * ::std::f64::consts::SQRT_2).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn same_cost_as_dijkstra_on_random_boards() {
+        use rand::{Rng, SeedableRng, StdRng};
+        const STATES: [BoxState; 6] = [
+            BoxState::Empty,
+            BoxState::Empty,
+            BoxState::Labyrinth,
+            BoxState::Road,
+            BoxState::Mud,
+            BoxState::Water,
+        ];
+        let mut rng = StdRng::from_seed(&[5][..]);
+        for _ in 0..200 {
+            let mut marked = Array::<LabyrinthEntry>::default(Dim(12, 12));
+            for entry in marked.iter_mut() {
+                entry.state = STATES[rng.gen_range(0, STATES.len())];
+            }
+            let start = (rng.gen_range(0, 12), rng.gen_range(0, 12));
+            let end = (rng.gen_range(0, 12), rng.gen_range(0, 12));
+            for &neighbourhood in &[Neighbourhood::Four, Neighbourhood::Eight] {
+                for &corner_cutting in &[CornerCutting::Allowed, CornerCutting::NoSqueezing, CornerCutting::Forbidden] {
+                    let movement = Movement {
+                        neighbourhood,
+                        corner_cutting,
+                    };
+                    let expected = a_star(&marked, start, end, &Zero, movement).map(|solution| solution.cost);
+                    for heuristic in &HEURISTICS {
+                        let cost = a_star(&marked, start, end, heuristic, movement).map(|solution| solution.cost);
+                        match (cost, expected) {
+                            (Some(cost), Some(expected)) => assert!((cost - expected).abs() < 1e-9, "{:?} {:?}", heuristic, movement),
+                            (cost, expected) => assert_eq!(cost, expected),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn squeezing_between_walls() {
+        let marked = board(&[".#", "#."]);
+        let solution = a_star(&marked, (0, 0), (1, 1), &Euclidean, eight_connected(CornerCutting::Allowed)).unwrap();
+        assert_eq!(solution.path, vec![(0, 0), (1, 1)]);
+        assert_eq!(a_star(&marked, (0, 0), (1, 1), &Euclidean, eight_connected(CornerCutting::NoSqueezing)), None);
+        assert_eq!(a_star(&marked, (0, 0), (1, 1), &Euclidean, eight_connected(CornerCutting::Forbidden)), None);
+    }
+
+    #[test]
+    fn cutting_a_single_corner() {
+        let marked = board(&[".#", ".."]);
+        let solution = a_star(&marked, (0, 0), (1, 1), &Euclidean, eight_connected(CornerCutting::NoSqueezing)).unwrap();
+        assert_eq!(solution.path, vec![(0, 0), (1, 1)]);
+        let solution = a_star(&marked, (0, 0), (1, 1), &Euclidean, eight_connected(CornerCutting::Forbidden)).unwrap();
+        assert_eq!(solution.path, vec![(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(solution.cost, 2.0);
+    }
+
+    #[test]
+    fn diagonal_step_into_terrain() {
+        let marked = board(&["..", ".%"]);
+        // the diagonal step into the mud costs 3 * sqrt(2), the detour only 1 + 3
+        let solution = a_star(&marked, (0, 0), (1, 1), &Zero, eight_connected(CornerCutting::Allowed)).unwrap();
+        assert_eq!(solution.path.len(), 3);
+        assert_eq!(solution.cost, 4.0);
+    }
+
+    #[test]
+    fn no_path() {
+        let marked = board(&[".#.", ".#.", ".#."]);
+        assert_eq!(a_star(&marked, (0, 0), (2, 2), &Manhattan, Movement::default()), None);
+    }
+
+    #[test]
+    fn start_on_wall() {
+        let marked = board(&["#.", ".."]);
+        assert_eq!(a_star(&marked, (0, 0), (1, 1), &Euclidean, Movement::default()), None);
+    }
+
+    #[test]
+    fn end_outside_of_board() {
+        let marked = board(&["..", ".."]);
+        assert_eq!(a_star(&marked, (0, 0), (2, 0), &Chebyshev, Movement::default()), None);
+    }
+
+    #[test]
+    fn step_by_step() {
+        let marked = board(&["...", ".#.", "..."]);
+        let mut search = AStar::new(&marked, (0, 0), (2, 2), Manhattan, Movement::default());
+        assert_eq!(
+            search.step(&marked),
+            SearchStep::Expanded {
+                closed: (0, 0),
+                opened: vec![(1, 0), (0, 1)],
+            }
+        );
+        let mut steps = 1;
+        let solution = loop {
+            match search.step(&marked) {
+                SearchStep::Expanded { closed, opened } => {
+                    assert!(closed != (1, 1));
+                    assert!(!opened.contains(&(1, 1)));
+                    steps += 1;
+                }
+                SearchStep::Finished(solution) => break solution.unwrap(),
+            }
+        };
+        assert_eq!(solution.cost, 4.0);
+        assert_eq!(solution.expanded, steps + 1);
+        assert_eq!(search.step(&marked), SearchStep::Finished(Some(solution)));
+    }
+
+    #[test]
+    fn step_without_path() {
+        let marked = board(&["#.", ".."]);
+        let mut search = AStar::new(&marked, (0, 0), (1, 1), Manhattan, Movement::default());
+        assert_eq!(search.step(&marked), SearchStep::Finished(None));
+    }
+
+    #[test]
+    fn heuristic_from_str() {
+        assert_eq!("manhattan".parse::<HeuristicKind>().unwrap(), HeuristicKind::Manhattan);
+        assert_eq!("dijkstra".parse::<HeuristicKind>().unwrap(), HeuristicKind::Zero);
+        for name in HeuristicKind::names() {
+            assert!(name.parse::<HeuristicKind>().is_ok());
+        }
+        assert!("octile".parse::<HeuristicKind>().is_err());
+        assert_eq!("8".parse::<Neighbourhood>().unwrap(), Neighbourhood::Eight);
+        assert_eq!(Neighbourhood::Four.toggled(), Neighbourhood::Eight);
+        assert!("6".parse::<Neighbourhood>().is_err());
+        for name in CornerCutting::names() {
+            assert!(name.parse::<CornerCutting>().is_ok());
+        }
+    }
+}
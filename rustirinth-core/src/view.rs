@@ -0,0 +1,115 @@
+use basic_types::Rectangle;
+use grid::{GeneralPointInBoardPixels, GeneralPointInWindowPixels, RectangleInBoardPixels, RectangleInWindowPixels};
+
+// Maps the board pixels of the labyrinth to the window:
+// window = board * scale + offset
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct View {
+    pub scale: f64,
+    pub offset: (f64, f64),
+}
+
+impl View {
+    pub const MINIMUM_SCALE: f64 = 0.05;
+    pub const MAXIMUM_SCALE: f64 = 16.0;
+    pub fn to_window(&self, point: GeneralPointInBoardPixels<f64>) -> GeneralPointInWindowPixels<f64> {
+        GeneralPointInWindowPixels::new(point.x() * self.scale + self.offset.0, point.y() * self.scale + self.offset.1)
+    }
+    pub fn from_window(&self, point: GeneralPointInWindowPixels<f64>) -> GeneralPointInBoardPixels<f64> {
+        GeneralPointInBoardPixels::new((point.x() - self.offset.0) / self.scale, (point.y() - self.offset.1) / self.scale)
+    }
+    // the smallest window rectangle containing the given one, cut off at the
+    // top and left border of the window; None if it is completely outside
+    pub fn rectangle_to_window(&self, rectangle: &RectangleInBoardPixels) -> Option<RectangleInWindowPixels> {
+        let top_left = self.to_window(GeneralPointInBoardPixels::new(f64::from(rectangle.x), f64::from(rectangle.y)));
+        let bottom_right = self.to_window(GeneralPointInBoardPixels::new(
+            f64::from(rectangle.x + rectangle.width),
+            f64::from(rectangle.y + rectangle.height),
+        ));
+        let (left, top) = (top_left.x().floor().max(0.0), top_left.y().floor().max(0.0));
+        let (right, bottom) = (bottom_right.x().ceil(), bottom_right.y().ceil());
+        if right <= left || bottom <= top || right > f64::from(u32::MAX) || bottom > f64::from(u32::MAX) {
+            return None;
+        }
+        Some(RectangleInWindowPixels::new(Rectangle {
+            x: left as u32,
+            y: top as u32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        }))
+    }
+    // changes the scale by the given factor, the point under the mouse
+    // pointer stays where it is
+    pub fn zoom_at(&mut self, factor: f64, pointer: GeneralPointInWindowPixels<f64>) {
+        let scale = (self.scale * factor).clamp(View::MINIMUM_SCALE, View::MAXIMUM_SCALE);
+        let fixed = self.from_window(pointer);
+        self.scale = scale;
+        self.offset = (pointer.x() - fixed.x() * scale, pointer.y() - fixed.y() * scale);
+    }
+    pub fn pan(&mut self, (delta_x, delta_y): (f64, f64)) {
+        self.offset = (self.offset.0 + delta_x, self.offset.1 + delta_y);
+    }
+}
+
+impl Default for View {
+    fn default() -> View {
+        View {
+            scale: 1.0,
+            offset: (0.0, 0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let view = View {
+            scale: 2.5,
+            offset: (-10.0, 20.0),
+        };
+        let window = GeneralPointInWindowPixels::new(0.0, 25.0);
+        assert_eq!(view.to_window(GeneralPointInBoardPixels::new(4.0, 2.0)), window);
+        assert_eq!(view.from_window(window), GeneralPointInBoardPixels::new(4.0, 2.0));
+    }
+
+    #[test]
+    fn zoom_keeps_pointer() {
+        let mut view = View::default();
+        view.zoom_at(2.0, GeneralPointInWindowPixels::new(100.0, 50.0));
+        assert_eq!(view.from_window(GeneralPointInWindowPixels::new(100.0, 50.0)), GeneralPointInBoardPixels::new(100.0, 50.0));
+        assert_eq!(view.to_window(GeneralPointInBoardPixels::new(0.0, 0.0)), GeneralPointInWindowPixels::new(-100.0, -50.0));
+        view.zoom_at(1000.0, GeneralPointInWindowPixels::new(0.0, 0.0));
+        assert_eq!(view.scale, View::MAXIMUM_SCALE);
+    }
+
+    #[test]
+    fn window_rectangle() {
+        let mut view = View {
+            scale: 0.5,
+            offset: (0.0, 0.0),
+        };
+        let rectangle = RectangleInBoardPixels::new(Rectangle {
+            x: 3,
+            y: 4,
+            width: 5,
+            height: 6,
+        });
+        assert_eq!(
+            view.rectangle_to_window(&rectangle),
+            Some(RectangleInWindowPixels::new(Rectangle {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 3,
+            }))
+        );
+        view.pan((-2.5, 0.0));
+        assert_eq!(view.rectangle_to_window(&rectangle).map(|window| (window.x, window.width)), Some((0, 2)));
+        view.pan((-10.0, 0.0));
+        assert_eq!(view.rectangle_to_window(&rectangle), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::SystemTime;
+use failure::Error;
+use rustirinth_core::file_format;
+use rustirinth_core::labyrinth::Labyrinth;
+
+// seconds between two snapshots of the board
+pub const INTERVAL: u32 = 30;
+const FILE_PREFIX: &str = "autosave-";
+const FILE_SUFFIX: &str = ".txt";
+
+// snapshots of the board in the XDG state directory: the snapshot is removed
+// when the game ends normally or the board is saved, so finding one of a
+// session which is no longer running means that it ended with unsaved changes
+#[derive(Debug)]
+pub struct Autosave {
+    // in the temporary directory if there is no state directory, every
+    // session has its own file named after its process id
+    path: PathBuf,
+    // the contents of the last snapshot, of the file the board was saved to or
+    // of the board when it was created or loaded, an unchanged board is not
+    // written again
+    written: Option<Vec<u8>>,
+}
+
+impl Autosave {
+    pub fn new() -> Autosave {
+        Autosave {
+            path: state_directory(env::var_os("XDG_STATE_HOME"), env::var_os("HOME"))
+                .unwrap_or_else(env::temp_dir)
+                .join(file_name(process::id())),
+            written: None,
+        }
+    }
+    // the newest snapshot which a session that is no longer running left
+    // behind, the snapshots of other running sessions are theirs
+    pub fn recovery_file(&self) -> Option<PathBuf> {
+        self.path.parent().and_then(|directory| left_behind(directory, is_running))
+    }
+    // the restored board becomes the snapshot of this session
+    pub fn adopt(&self, recovery_file: &Path) -> Result<(), Error> {
+        fs::rename(recovery_file, &self.path).map_err(|error| file_error(error, "Could not move", recovery_file))
+    }
+    // the board which was not restored is gone
+    pub fn reject(&self, recovery_file: &Path) -> Result<(), Error> {
+        remove(recovery_file)
+    }
+    pub fn snapshot(&mut self, labyrinth: &Labyrinth) -> Result<(), Error> {
+        let contents = contents(labyrinth)?;
+        if self.written.as_ref() == Some(&contents) {
+            return Ok(());
+        }
+        self.write(&contents)?;
+        self.written = Some(contents);
+        Ok(())
+    }
+    // after a fatal error the board is written even if it did not change,
+    // returns where it went
+    pub fn emergency_save(&self, labyrinth: &Labyrinth) -> Result<PathBuf, Error> {
+        self.write(&contents(labyrinth)?)
+    }
+    // a new or loaded board, only the changes to it are worth a snapshot
+    pub fn start_from(&mut self, labyrinth: &Labyrinth) -> Result<(), Error> {
+        self.written = Some(contents(labyrinth)?);
+        Ok(())
+    }
+    pub fn is_unchanged(&self, labyrinth: &Labyrinth) -> bool {
+        match contents(labyrinth) {
+            Ok(contents) => self.written.as_ref() == Some(&contents),
+            Err(_) => false,
+        }
+    }
+    // the board is the same as in its file, there is nothing to recover
+    pub fn mark_saved(&mut self, labyrinth: &Labyrinth) -> Result<(), Error> {
+        self.start_from(labyrinth)?;
+        self.discard()
+    }
+    pub fn discard(&self) -> Result<(), Error> {
+        remove(&self.path)
+    }
+    fn write(&self, contents: &[u8]) -> Result<PathBuf, Error> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory).map_err(|error| file_error(error, "Could not create", directory))?;
+        }
+        file_format::write_atomically(&self.path, contents)?;
+        Ok(self.path.clone())
+    }
+}
+
+fn file_name(session: u32) -> String {
+    format!("{}{}{}", FILE_PREFIX, session, FILE_SUFFIX)
+}
+
+fn session(file_name: &str) -> Option<u32> {
+    file_name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?.parse().ok()
+}
+
+// a process with this id exists, on systems without /proc every other
+// session counts as ended
+fn is_running(session: u32) -> bool {
+    Path::new("/proc").join(session.to_string()).exists()
+}
+
+fn left_behind<F>(directory: &Path, is_running: F) -> Option<PathBuf>
+where
+    F: Fn(u32) -> bool,
+{
+    let own_session = process::id();
+    fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| match entry.file_name().to_str().and_then(session) {
+            Some(session) => session != own_session && !is_running(session),
+            None => false,
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|metadata| metadata.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((modified, entry.path())).filter(|(_, path)| path.is_file())
+        })
+        .max()
+        .map(|(_, path)| path)
+}
+
+fn remove(path: &Path) -> Result<(), Error> {
+    if let Err(error) = fs::remove_file(path) {
+        if error.kind() != io::ErrorKind::NotFound {
+            return Err(file_error(error, "Could not remove", path));
+        }
+    }
+    Ok(())
+}
+
+fn contents(labyrinth: &Labyrinth) -> Result<Vec<u8>, Error> {
+    let mut contents = Vec::new();
+    file_format::write(labyrinth, &mut contents)?;
+    Ok(contents)
+}
+
+fn file_error(error: io::Error, action: &str, path: &Path) -> Error {
+    Error::from(error).context(format!("{} \"{}\"", action, path.display())).into()
+}
+
+// $XDG_STATE_HOME/rustirinth, $XDG_STATE_HOME defaults to ~/.local/state;
+// relative paths are invalid according to the specification and ignored
+fn state_directory(xdg_state_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    let absolute = |value: OsString| Some(PathBuf::from(value)).filter(|path| path.is_absolute());
+    xdg_state_home
+        .and_then(absolute)
+        .or_else(|| home.and_then(absolute).map(|home| home.join(".local").join("state")))
+        .map(|directory| directory.join("rustirinth"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rustirinth_core::grid::PointInGridCoordinates;
+    use rustirinth_core::labyrinth::BoxState;
+
+    #[test]
+    fn state_directory_from_environment() {
+        let directory = |xdg: Option<&str>, home: Option<&str>| state_directory(xdg.map(OsString::from), home.map(OsString::from));
+        assert_eq!(directory(Some("/var/state"), Some("/home/a")), Some(PathBuf::from("/var/state/rustirinth")));
+        assert_eq!(directory(None, Some("/home/a")), Some(PathBuf::from("/home/a/.local/state/rustirinth")));
+        assert_eq!(directory(Some("state"), Some("/home/a")), Some(PathBuf::from("/home/a/.local/state/rustirinth")));
+        assert_eq!(directory(Some(""), None), None);
+    }
+
+    #[test]
+    fn snapshot_only_after_changes() {
+        let directory = env::temp_dir().join(format!("rustirinth-autosave-{}", ::std::process::id()));
+        let mut autosave = Autosave {
+            path: directory.join(file_name(process::id())),
+            written: None,
+        };
+        let mut labyrinth = Labyrinth::with_box_count(16, 4, 3).unwrap();
+        autosave.start_from(&labyrinth).unwrap();
+        assert!(autosave.is_unchanged(&labyrinth));
+        autosave.snapshot(&labyrinth).unwrap();
+        assert_eq!(autosave.recovery_file(), None);
+        labyrinth.set_box_states(&[PointInGridCoordinates::new(1, 1)], BoxState::Labyrinth, |_| Ok(())).unwrap();
+        assert!(!autosave.is_unchanged(&labyrinth));
+        autosave.snapshot(&labyrinth).unwrap();
+        assert!(directory.join(file_name(process::id())).is_file());
+        autosave.discard().unwrap();
+        assert!(!directory.join(file_name(process::id())).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn recover_only_ended_sessions() {
+        let directory = env::temp_dir().join(format!("rustirinth-recovery-{}", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let autosave = Autosave {
+            path: directory.join(file_name(process::id())),
+            written: None,
+        };
+        // the own snapshot and the one of session 2, which is still running
+        for &session in &[process::id(), 2] {
+            fs::write(directory.join(file_name(session)), "").unwrap();
+        }
+        fs::write(directory.join("notes.txt"), "").unwrap();
+        assert_eq!(left_behind(&directory, |session| session == 2), None);
+        fs::write(directory.join(file_name(1)), "").unwrap();
+        let recovery_file = left_behind(&directory, |session| session == 2).unwrap();
+        assert_eq!(recovery_file, directory.join(file_name(1)));
+        autosave.adopt(&recovery_file).unwrap();
+        assert_eq!(left_behind(&directory, |session| session == 2), None);
+        assert!(directory.join(file_name(2)).is_file());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
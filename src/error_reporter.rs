@@ -0,0 +1,92 @@
+use std;
+use gtk;
+
+use failure::{Error, Fail};
+use rustirinth_core::basic_types::{self, Severity};
+use state::LabyrinthState;
+
+// errors in the signal handlers: warnings and recoverable errors are shown in
+// the info bar over the top of the board and the game goes on, fatal ones end the
+// program after the board has been saved
+#[derive(Debug, Clone)]
+pub struct ErrorReporter {
+    info_bar: gtk::InfoBar,
+    label: gtk::Label,
+}
+
+impl ErrorReporter {
+    pub fn new(info_bar: &gtk::InfoBar, label: &gtk::Label) -> ErrorReporter {
+        ErrorReporter {
+            info_bar: info_bar.clone(),
+            label: label.clone(),
+        }
+    }
+    pub fn handle(&self, error: &Error, state: &LabyrinthState) {
+        match basic_types::severity(error) {
+            Severity::Warning => self.show_as(error, gtk::MessageType::Warning),
+            Severity::Recoverable => self.show(error),
+            Severity::Fatal => {
+                emergency_save(state);
+                fatal_error(error);
+            }
+        }
+    }
+    // replaces the previous message, the info bar stays until it is closed
+    pub fn show(&self, error: &Error) {
+        self.show_as(error, gtk::MessageType::Error);
+    }
+    fn show_as(&self, error: &Error, message_type: gtk::MessageType) {
+        use gtk::{InfoBarExt, LabelExt, WidgetExt};
+        match message_type {
+            gtk::MessageType::Warning => report(error, "Warning: "),
+            _ => report_error(error),
+        }
+        self.label.set_text(&messages(error).join("\n"));
+        self.info_bar.set_message_type(message_type);
+        self.label.show();
+        self.info_bar.show();
+    }
+}
+
+pub fn fatal_error(error: &Error) {
+    report_error(error);
+    std::process::exit(-1);
+}
+
+pub fn report_error(error: &Error) {
+    report(error, "Error: ");
+}
+
+fn report(error: &Error, prefix: &str) {
+    use std::io::Write;
+    let stderr = &mut ::std::io::stderr();
+    for (index, message) in messages(error).iter().enumerate() {
+        let _ = writeln!(stderr, "{}{}", if index == 0 { prefix } else { "" }, message);
+    }
+}
+
+// the error followed by its causes, one line each
+fn messages(error: &Error) -> Vec<String> {
+    let mut messages = vec![error.to_string()];
+    let mut fail: &dyn Fail = error.cause();
+    while let Some(cause) = fail.cause() {
+        messages.push(format!("Caused by: {}", cause));
+        fail = cause;
+    }
+    messages
+}
+
+// writes the board where the next start offers to recover it, so a fatal
+// error does not lose the drawing; the file the board was loaded from is not
+// touched as the board may be broken
+fn emergency_save(state: &LabyrinthState) {
+    use std::io::Write;
+    if let Some(labyrinth) = state.labyrinth.as_ref().or(state.loaded.as_ref()) {
+        match state.autosave.emergency_save(labyrinth) {
+            Ok(path) => {
+                let _ = writeln!(&mut ::std::io::stderr(), "The board was saved to \"{}\"", path.display());
+            }
+            Err(error) => report_error(&error),
+        }
+    }
+}
@@ -2,32 +2,175 @@ use cairo;
 use gtk;
 use gdk;
 
-use std::cmp::{max, min};
-use basic_types::{convert, Color, GeneralRectangle, IsAColor, IsARectangle, IsARectangularArea, Rectangle};
-use labyrinth::{BoxState, Labyrinth, LabyrinthState};
+use std::path::Path;
+use rustirinth_core::basic_types::{convert, GeneralRectangle, LabyrinthError, Rectangle};
+use rustirinth_core::grid::{GeneralPointInWindowPixels, PointInGridCoordinates, RectangleInBoardPixels};
+use rustirinth_core::labyrinth::{BoxState, Labyrinth};
+use rustirinth_core::play::Player;
+use rustirinth_core::shapes::Shape;
+use rustirinth_core::view::View;
+use export::{self, ExportFormat};
+use error_reporter::ErrorReporter;
+use gtk_adapters::point_from_gdk;
+use png_import;
+use renderer::Renderer;
+use state::{LabyrinthState, ShapeDrag, Tool};
+use rustirinth_core::file_format;
 use failure::Error;
 use gtk::WidgetExt;
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FileOperation {
+    Save,
+    Open,
+    Export,
+    Import,
+}
+
 #[derive(Debug)]
-pub struct EventHandler;
+pub struct EventHandler {
+    renderer: Renderer,
+    // shows the warning when the labyrinth does not fit into the window
+    errors: ErrorReporter,
+}
 
 impl EventHandler {
-    pub fn new() -> EventHandler {
-        EventHandler {}
+    pub fn new(errors: ErrorReporter) -> EventHandler {
+        EventHandler {
+            renderer: Renderer::new(true),
+            errors,
+        }
     }
     pub fn on_size_allocate(&mut self, state: &mut LabyrinthState, rect: &Rectangle) -> Result<(), Error> {
+        // without any space the labyrinth is kept until the window gets larger again
         if rect.width > 0 && rect.height > 0 {
             let width = convert(rect.width)?;
             let height = convert(rect.height)?;
-            state.labyrinth = Some(Labyrinth::new(state.box_size, width, height));
+            self.fit_into(state, width, height)?;
+        }
+        Ok(())
+    }
+    // adapts the labyrinth to the size of the drawing area, without losing
+    // any box which is not empty: with fixed dimensions the boxes are scaled,
+    // otherwise boxes are added or removed
+    fn fit_into(&self, state: &mut LabyrinthState, width: u32, height: u32) -> Result<(), Error> {
+        // a shape dragged on the old board may not fit onto the new one
+        state.drag = None;
+        // the autosave starts from a loaded or a new labyrinth, resizing an
+        // unchanged one does not change it either
+        let (mut labyrinth, unchanged) = match (state.loaded.take(), state.labyrinth.take()) {
+            (Some(loaded), _) => (loaded, true),
+            (None, Some(labyrinth)) => {
+                let unchanged = state.autosave.is_unchanged(&labyrinth);
+                (labyrinth, unchanged)
+            }
+            (None, None) => (Labyrinth::with_box_count(state.box_size, 0, 0)?, true),
+        };
+        match state.dimensions {
+            Some((x_box_cnt, y_box_cnt)) => {
+                labyrinth.resize(x_box_cnt, y_box_cnt, |_| Ok(()))?;
+                let box_size = Labyrinth::fitting_box_size(labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt, width, height);
+                labyrinth.set_box_size(box_size)?;
+            }
+            None => {
+                let (x_box_cnt, y_box_cnt) = Labyrinth::box_count(labyrinth.grid.box_size, width, height);
+                labyrinth.resize(x_box_cnt, y_box_cnt, |_| Ok(()))?;
+            }
+        }
+        labyrinth.center(width, height);
+        if labyrinth.search_options != state.search_options {
+            labyrinth.set_search_options(state.search_options, |_| Ok(()))?;
+        }
+        let fits = labyrinth.fits_into(width, height);
+        let warning = if !fits && !state.too_small {
+            Some(LabyrinthError::DoesNotFit {
+                x_box_cnt: labyrinth.grid.x_box_cnt,
+                y_box_cnt: labyrinth.grid.y_box_cnt,
+            })
         } else {
-            state.labyrinth = None;
+            None
+        };
+        state.too_small = !fits;
+        if unchanged {
+            state.autosave.start_from(&labyrinth)?;
+        }
+        state.labyrinth = Some(labyrinth);
+        // the labyrinth is usable anyway, so this is not returned as an error
+        if let Some(warning) = warning {
+            self.errors.handle(&warning.into(), state);
+        }
+        Ok(())
+    }
+    // Ctrl+S, Ctrl+O, Ctrl+E and Ctrl+I need a file chooser, which has to run without
+    // any borrow of the state, as it processes draw events while it is open
+    pub fn file_operation(event: &gdk::EventKey) -> Option<FileOperation> {
+        use gdk::enums::key;
+        if event.get_state() & gdk::ModifierType::CONTROL_MASK == gdk::ModifierType::empty() {
+            return None;
         }
+        match event.get_keyval() {
+            key::s => Some(FileOperation::Save),
+            key::o => Some(FileOperation::Open),
+            key::e => Some(FileOperation::Export),
+            key::i => Some(FileOperation::Import),
+            _ => None,
+        }
+    }
+    pub fn on_file_chosen(
+        &mut self,
+        drawing_area: &gtk::DrawingArea,
+        state: &mut LabyrinthState,
+        operation: FileOperation,
+        path: &Path,
+    ) -> Result<(), Error> {
+        match operation {
+            FileOperation::Save => {
+                if let Some(ref labyrinth) = state.labyrinth {
+                    file_format::save(labyrinth, path)?;
+                }
+            }
+            FileOperation::Open => {
+                let labyrinth = file_format::load(path)?;
+                self.replace_labyrinth(drawing_area, state, labyrinth)?;
+            }
+            FileOperation::Export => {
+                // an exported image cannot be loaded again, so it is not the file of the board
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    export::export(labyrinth, path, ExportFormat::from_path(path)?, 1.0, true)?;
+                }
+                return Ok(());
+            }
+            FileOperation::Import => {
+                let labyrinth = png_import::load(path, state.box_size, &state.import_options)?;
+                self.replace_labyrinth(drawing_area, state, labyrinth)?;
+                // the board is new, saving must not overwrite the image
+                state.file = None;
+                return Ok(());
+            }
+        }
+        if let Some(ref labyrinth) = state.labyrinth {
+            state.autosave.mark_saved(labyrinth)?;
+        }
+        state.file = Some(path.to_path_buf());
+        Ok(())
+    }
+    // a loaded or imported labyrinth keeps its number of boxes
+    fn replace_labyrinth(&self, drawing_area: &gtk::DrawingArea, state: &mut LabyrinthState, labyrinth: Labyrinth) -> Result<(), Error> {
+        state.dimensions = Some((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt));
+        state.loaded = Some(labyrinth);
+        state.player = None;
+        let width = convert(drawing_area.get_allocated_width())?;
+        let height = convert(drawing_area.get_allocated_height())?;
+        self.fit_into(state, width, height)?;
+        drawing_area.queue_draw();
         Ok(())
     }
     pub fn on_draw(&mut self, state: &mut LabyrinthState, cairo_context: &cairo::Context) -> Result<(), Error> {
         if let Some(labyrinth) = state.labyrinth.as_mut() {
-            self.draw(labyrinth, cairo_context)
+            match state.player {
+                Some(ref player) => self.draw(labyrinth, None, None, Some(player), cairo_context),
+                None => self.draw(labyrinth, state.drag.as_ref(), state.cursor, None, cairo_context),
+            }
         } else {
             Ok(())
         }
@@ -38,166 +181,418 @@ impl EventHandler {
         state: &mut LabyrinthState,
         event: &gdk::EventButton,
     ) -> Result<(), Error> {
+        if event.get_button() == 2 {
+            /* middle mouse button: dragging moves the view */
+            state.pan_anchor = Some(point_from_gdk(event.get_position()));
+            return Ok(());
+        }
+        if state.player.is_some() {
+            // the board cannot be changed while playing
+            return Ok(());
+        }
+        state.painting_with_keys = false;
+        let brush = state.brush;
+        let tool = state.tool;
         if let Some(ref mut labyrinth) = state.labyrinth {
-            let box_state = match event.get_button() {
-                1 => {
+            let control_pressed = event.get_state() & gdk::ModifierType::CONTROL_MASK != gdk::ModifierType::empty();
+            let box_state = match (event.get_button(), control_pressed) {
+                (1, false) => {
                     /* left mouse button */
-                    Some(BoxState::Labyrinth)
+                    Some(brush)
                 }
-                3 => {
+                (3, false) => {
                     /* right mouse button */
                     Some(BoxState::Empty)
                 }
+                (1, true) => {
+                    /* control + left mouse button */
+                    Some(BoxState::Start)
+                }
+                (3, true) => {
+                    /* control + right mouse button */
+                    Some(BoxState::End)
+                }
                 _ => None,
             };
-            if let Some(state) = box_state {
-                self.handle_mark_box(drawing_area, labyrinth, event.get_position(), state)?;
+            match (box_state, tool) {
+                (Some(box_state), Tool::Shape(shape)) if !control_pressed => {
+                    // the shape is only drawn when the button is released
+                    if let Some(clicked_box) = labyrinth.pixel_to_box(point_from_gdk(event.get_position())) {
+                        let drag = ShapeDrag {
+                            shape,
+                            state: box_state,
+                            button: event.get_button(),
+                            from: clicked_box,
+                            to: clicked_box,
+                        };
+                        EventHandler::queue_draw_drag(drawing_area, labyrinth, &drag)?;
+                        state.drag = Some(drag);
+                    }
+                }
+                (Some(box_state), Tool::Bucket) if !control_pressed => {
+                    if let Some(clicked_box) = labyrinth.pixel_to_box(point_from_gdk(event.get_position())) {
+                        labyrinth.begin_edit();
+                        labyrinth.flood_fill(clicked_box, box_state, EventHandler::redraw(drawing_area, labyrinth.view))?;
+                    }
+                }
+                (Some(box_state), _) => {
+                    // everything painted until the next button press is one undo step
+                    labyrinth.begin_edit();
+                    self.handle_mark_box(drawing_area, labyrinth, point_from_gdk(event.get_position()), box_state)?;
+                }
+                (None, _) => {}
             }
         }
         Ok(())
     }
+    pub fn on_button_release(
+        &mut self,
+        drawing_area: &gtk::DrawingArea,
+        state: &mut LabyrinthState,
+        event: &gdk::EventButton,
+    ) -> Result<(), Error> {
+        let result = match (state.drag, state.labyrinth.as_mut()) {
+            (Some(drag), Some(labyrinth)) if drag.button == event.get_button() => {
+                state.drag = None;
+                // the preview disappears, the shape is drawn instead
+                EventHandler::queue_draw_drag(drawing_area, labyrinth, &drag)?;
+                labyrinth.begin_edit();
+                labyrinth.set_box_states(&drag.boxes(), drag.state, EventHandler::redraw(drawing_area, labyrinth.view))
+            }
+            _ => Ok(()),
+        };
+        // the stroke is over, the next change is a new undo step
+        if let Some(ref mut labyrinth) = state.labyrinth {
+            labyrinth.end_edit();
+        }
+        result
+    }
     pub fn on_motion_notify(
         &mut self,
         drawing_area: &gtk::DrawingArea,
         state: &mut LabyrinthState,
         event: &gdk::EventMotion,
     ) -> Result<(), Error> {
+        if event.get_state() & gdk::ModifierType::BUTTON2_MASK != gdk::ModifierType::empty() {
+            return self.pan(drawing_area, state, point_from_gdk(event.get_position()));
+        }
+        if let (Some(drag), Some(labyrinth)) = (state.drag.as_mut(), state.labyrinth.as_ref()) {
+            // only the preview changes while a shape is dragged
+            match labyrinth.pixel_to_box(point_from_gdk(event.get_position())) {
+                Some(current_box) if current_box != drag.to => {
+                    EventHandler::queue_draw_drag(drawing_area, labyrinth, drag)?;
+                    drag.to = current_box;
+                    EventHandler::queue_draw_drag(drawing_area, labyrinth, drag)?;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        if state.tool != Tool::Pencil || state.player.is_some() {
+            // the other tools act on press and release only
+            return Ok(());
+        }
+        let brush = state.brush;
         if let Some(ref mut labyrinth) = state.labyrinth {
+            if event.get_state() & gdk::ModifierType::CONTROL_MASK != gdk::ModifierType::empty() {
+                // control + click places start and end, dragging does not paint
+                return Ok(());
+            }
             if event.get_state() & gdk::ModifierType::BUTTON1_MASK != gdk::ModifierType::empty() {
                 self.handle_mark_box(
                     drawing_area,
                     labyrinth,
-                    event.get_position(),
-                    BoxState::Labyrinth,
+                    point_from_gdk(event.get_position()),
+                    brush,
                 )?;
             } else if event.get_state() & gdk::ModifierType::BUTTON3_MASK != gdk::ModifierType::empty() {
                 self.handle_mark_box(
                     drawing_area,
                     labyrinth,
-                    event.get_position(),
+                    point_from_gdk(event.get_position()),
                     BoxState::Empty,
                 )?;
             }
         }
         Ok(())
     }
-    fn draw(&mut self, labyrinth: &mut Labyrinth, cairo_context: &cairo::Context) -> Result<(), Error> {
-        let (top_left_x, top_left_y, bottom_right_x, bottom_right_y) = cairo_context.clip_extents();
-        let draw_area = Rectangle::approx_from(&(
-            top_left_x,
-            top_left_y,
-            bottom_right_x - top_left_x,
-            bottom_right_y - top_left_y,
-        ))?;
-        if let Some(intersection) = draw_area.intersect(&labyrinth.rectangle) {
-            self.draw_axes(&intersection, labyrinth, cairo_context)?;
-            self.draw_boxes(&intersection, labyrinth, cairo_context)?;
-            // self.draw_legend(&intersection, labyrinth, cairo_context)?;
+    pub fn on_scroll(
+        &mut self,
+        drawing_area: &gtk::DrawingArea,
+        state: &mut LabyrinthState,
+        event: &gdk::EventScroll,
+    ) -> Result<(), Error> {
+        const ZOOM_FACTOR: f64 = 1.25;
+        let factor = match event.get_direction() {
+            gdk::ScrollDirection::Up => ZOOM_FACTOR,
+            gdk::ScrollDirection::Down => 1.0 / ZOOM_FACTOR,
+            _ => return Ok(()),
+        };
+        if let Some(ref mut labyrinth) = state.labyrinth {
+            labyrinth.view.zoom_at(factor, point_from_gdk(event.get_position()));
+            drawing_area.queue_draw();
         }
         Ok(())
     }
-    fn draw_axes(&self, draw_area: &Rectangle, labyrinth: &Labyrinth, cairo_context: &cairo::Context) -> Result<(), Error> {
-        let color = Color::get_black();
-        cairo_context.save();
-        cairo_context.set_source_rgb(color.red(), color.green(), color.blue());
-
-        self.draw_axes_x(draw_area, labyrinth, cairo_context)?;
-        self.draw_axes_y(draw_area, labyrinth, cairo_context)?;
-
-        cairo_context.stroke();
-        cairo_context.restore();
+    fn pan(
+        &mut self,
+        drawing_area: &gtk::DrawingArea,
+        state: &mut LabyrinthState,
+        position: GeneralPointInWindowPixels<f64>,
+    ) -> Result<(), Error> {
+        if let (Some(anchor), Some(ref mut labyrinth)) = (state.pan_anchor, state.labyrinth.as_mut()) {
+            labyrinth.view.pan((position.x() - anchor.x(), position.y() - anchor.y()));
+            drawing_area.queue_draw();
+        }
+        state.pan_anchor = Some(position);
         Ok(())
     }
-    fn draw_axes_x(&self, draw_area: &Rectangle, labyrinth: &Labyrinth, cairo_context: &cairo::Context) -> Result<(), Error> {
-        let start_x_cnt = (draw_area.top_left_x() - labyrinth.rectangle.x + labyrinth.box_size - 1) / labyrinth.box_size;
-        let end_x_cnt = min(
-            labyrinth.x_box_cnt + 1,
-            (draw_area.bottom_right_x() - labyrinth.rectangle.x + labyrinth.box_size - 1) / labyrinth.box_size,
-        );
-
-        for x_cnt in start_x_cnt..end_x_cnt {
-            let start_x = labyrinth.rectangle.x + labyrinth.box_size * x_cnt;
-            let start_y = max(labyrinth.rectangle.y, draw_area.top_left_y());
-            self.draw_line(
-                Rectangle {
-                    x: start_x,
-                    y: start_y,
-                    width: 0,
-                    height: labyrinth.rectangle.height,
-                },
-                draw_area,
-                cairo_context,
-            )?;
+    pub fn on_key_press(
+        &mut self,
+        drawing_area: &gtk::DrawingArea,
+        state: &mut LabyrinthState,
+        event: &gdk::EventKey,
+    ) -> Result<(), Error> {
+        use gdk::enums::key;
+        if state.player.is_some() {
+            return EventHandler::on_play_key(drawing_area, state, event.get_keyval());
+        }
+        let control_pressed = event.get_state() & gdk::ModifierType::CONTROL_MASK != gdk::ModifierType::empty();
+        let shift_pressed = event.get_state() & gdk::ModifierType::SHIFT_MASK != gdk::ModifierType::empty();
+        match event.get_keyval() {
+            // with shift, z arrives as Z
+            key::z | key::Z if control_pressed => {
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    let redraw = EventHandler::redraw(drawing_area, labyrinth.view);
+                    if shift_pressed {
+                        labyrinth.redo(redraw)?;
+                    } else {
+                        labyrinth.undo(redraw)?;
+                    }
+                }
+            }
+            key::_0 | key::KP_0 => {
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    labyrinth.view = View::default();
+                    drawing_area.queue_draw();
+                }
+            }
+            // with shift, the letters arrive in upper case
+            key::Left | key::h | key::H => EventHandler::move_cursor(drawing_area, state, (-1, 0), shift_pressed)?,
+            key::Right | key::l | key::L => EventHandler::move_cursor(drawing_area, state, (1, 0), shift_pressed)?,
+            key::Up | key::k | key::K => EventHandler::move_cursor(drawing_area, state, (0, -1), shift_pressed)?,
+            key::Down | key::j | key::J => EventHandler::move_cursor(drawing_area, state, (0, 1), shift_pressed)?,
+            key::space => EventHandler::toggle_wall(drawing_area, state)?,
+            key::F5 => EventHandler::toggle_play(drawing_area, state)?,
+            key::d => state.tool = Tool::Pencil,
+            key::t => state.tool = Tool::Shape(Shape::Line),
+            key::r => state.tool = Tool::Shape(Shape::Rectangle),
+            key::f => state.tool = Tool::Shape(Shape::FilledRectangle),
+            key::b => state.tool = Tool::Bucket,
+            key::_1 | key::KP_1 => state.brush = BoxState::Labyrinth,
+            key::_2 | key::KP_2 => state.brush = BoxState::Mud,
+            key::_3 | key::KP_3 => state.brush = BoxState::Water,
+            key::_4 | key::KP_4 => state.brush = BoxState::Road,
+            key::a => {
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    labyrinth.start_animation(EventHandler::redraw(drawing_area, labyrinth.view))?;
+                    state.animation.paused = false;
+                }
+            }
+            key::g => {
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    labyrinth.generate_maze(&state.generator, &mut state.rng, EventHandler::redraw(drawing_area, labyrinth.view))?;
+                }
+            }
+            key::p => state.animation.paused = !state.animation.paused,
+            key::period | key::KP_Decimal => {
+                state.animation.paused = true;
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    labyrinth.animation_step(EventHandler::redraw(drawing_area, labyrinth.view))?;
+                }
+            }
+            key::plus | key::KP_Add => state.animation.faster(),
+            key::minus | key::KP_Subtract => state.animation.slower(),
+            key::n => {
+                let movement = &mut state.search_options.movement;
+                movement.neighbourhood = movement.neighbourhood.toggled();
+                if let Some(ref mut labyrinth) = state.labyrinth {
+                    labyrinth.set_search_options(state.search_options, EventHandler::redraw(drawing_area, labyrinth.view))?;
+                }
+            }
+            _ => {}
         }
         Ok(())
     }
-    fn draw_axes_y(&self, draw_area: &Rectangle, labyrinth: &Labyrinth, cairo_context: &cairo::Context) -> Result<(), Error> {
-        let start_y_cnt = (draw_area.top_left_y() - labyrinth.rectangle.y + labyrinth.box_size - 1) / labyrinth.box_size;
-        let end_y_cnt = min(
-            labyrinth.y_box_cnt + 1,
-            (draw_area.bottom_right_y() - labyrinth.rectangle.y + labyrinth.box_size - 1) / labyrinth.box_size,
-        );
-
-        for y_cnt in start_y_cnt..end_y_cnt {
-            let start_x = max(labyrinth.rectangle.x, draw_area.top_left_x());
-            let start_y = labyrinth.rectangle.y + labyrinth.box_size * y_cnt;
-            self.draw_line(
-                Rectangle {
-                    x: start_x,
-                    y: start_y,
-                    width: labyrinth.rectangle.width,
-                    height: 0,
+    pub fn on_key_release(&mut self, state: &mut LabyrinthState, event: &gdk::EventKey) {
+        use gdk::enums::key;
+        let keyval = event.get_keyval();
+        if keyval == key::Shift_L || keyval == key::Shift_R {
+            EventHandler::end_painting_with_keys(state);
+        }
+    }
+    // in play mode the keys move the player, everything else waits until
+    // the board is edited again
+    fn on_play_key(drawing_area: &gtk::DrawingArea, state: &mut LabyrinthState, keyval: u32) -> Result<(), Error> {
+        use gdk::enums::key;
+        let direction = match keyval {
+            key::Left | key::h => (-1, 0),
+            key::Right | key::l => (1, 0),
+            key::Up | key::k => (0, -1),
+            key::Down | key::j => (0, 1),
+            key::F5 | key::Escape => return EventHandler::toggle_play(drawing_area, state),
+            _ => return Ok(()),
+        };
+        if let (Some(player), Some(labyrinth)) = (state.player.as_mut(), state.labyrinth.as_ref()) {
+            if let Some(from) = player.step(labyrinth, direction) {
+                if player.finished.is_some() {
+                    // the summary covers the middle of the board
+                    drawing_area.queue_draw();
+                } else {
+                    // only the box which was left and the one which was entered change
+                    let step = labyrinth.segment_to_pixel((from, player.position));
+                    EventHandler::queue_draw_rectangle(drawing_area, &labyrinth.view, &step)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    // the player starts on the start box, leaving play mode removes the
+    // player and the trail
+    fn toggle_play(drawing_area: &gtk::DrawingArea, state: &mut LabyrinthState) -> Result<(), Error> {
+        if state.player.take().is_none() {
+            if let Some(ref labyrinth) = state.labyrinth {
+                state.player = Some(Player::new(labyrinth)?);
+                // a shape dragged with the mouse is dropped
+                state.drag = None;
+            }
+        }
+        drawing_area.queue_draw();
+        Ok(())
+    }
+    // the first cursor key only shows the cursor, then it moves one box at a
+    // time; with shift the box it leaves and the one it enters are painted
+    fn move_cursor(drawing_area: &gtk::DrawingArea, state: &mut LabyrinthState, direction: (i32, i32), paint: bool) -> Result<(), Error> {
+        if let Some(ref mut labyrinth) = state.labyrinth {
+            let from = match state.cursor.and_then(|cursor| EventHandler::cursor_box(labyrinth, cursor)) {
+                Some(from) => from,
+                None => match EventHandler::cursor_box(labyrinth, PointInGridCoordinates::new(0, 0)) {
+                    Some(first) => {
+                        state.cursor = Some(first);
+                        let first_box = labyrinth.segment_to_pixel((first, first));
+                        return EventHandler::queue_draw_rectangle(drawing_area, &labyrinth.view, &first_box);
+                    }
+                    None => return Ok(()),
                 },
-                draw_area,
-                cairo_context,
-            )?;
+            };
+            let to = labyrinth.grid.neighbour(from, direction).unwrap_or(from);
+            state.cursor = Some(to);
+            if paint {
+                if !state.painting_with_keys {
+                    labyrinth.begin_edit();
+                    state.painting_with_keys = true;
+                }
+                labyrinth.set_box_states(&[from, to], state.brush, EventHandler::redraw(drawing_area, labyrinth.view))?;
+            } else if state.painting_with_keys {
+                labyrinth.end_edit();
+                state.painting_with_keys = false;
+            }
+            EventHandler::queue_draw_rectangle(drawing_area, &labyrinth.view, &labyrinth.segment_to_pixel((from, to)))?;
         }
         Ok(())
     }
-    fn draw_line(&self, line: Rectangle, draw_area: &Rectangle, cairo_context: &cairo::Context) -> Result<(), Error> {
-        match draw_area
-            .intersect(&line)
-            .map(|x| x.approx_to::<f64, GeneralRectangle<f64>>())
-        {
-            Some(Ok(intersection)) => {
-                cairo_context.move_to(intersection.top_left_x(), intersection.top_left_y());
-                cairo_context.line_to(intersection.bottom_right_x(), intersection.bottom_right_y());
-                Ok(())
+    // every toggle is an undo step of its own
+    fn toggle_wall(drawing_area: &gtk::DrawingArea, state: &mut LabyrinthState) -> Result<(), Error> {
+        EventHandler::end_painting_with_keys(state);
+        if let Some(ref mut labyrinth) = state.labyrinth {
+            if let Some(cursor) = EventHandler::cursor_box(labyrinth, state.cursor.unwrap_or_else(|| PointInGridCoordinates::new(0, 0))) {
+                state.cursor = Some(cursor);
+                let toggled = match labyrinth.box_state(cursor) {
+                    Some(BoxState::Labyrinth) => BoxState::Empty,
+                    _ => BoxState::Labyrinth,
+                };
+                labyrinth.set_box_states(&[cursor], toggled, EventHandler::redraw(drawing_area, labyrinth.view))?;
+                EventHandler::queue_draw_rectangle(drawing_area, &labyrinth.view, &labyrinth.segment_to_pixel((cursor, cursor)))?;
             }
-            Some(Err(err)) => Err(err),
-            None => Ok(()),
+        }
+        Ok(())
+    }
+    fn end_painting_with_keys(state: &mut LabyrinthState) {
+        state.painting_with_keys = false;
+        if let Some(ref mut labyrinth) = state.labyrinth {
+            labyrinth.end_edit();
         }
     }
-    fn draw_boxes(&mut self, drawing_area: &Rectangle, labyrinth: &mut Labyrinth, cairo_context: &cairo::Context) -> Result<(), Error> {
+    // the cursor stays on the board when the board becomes smaller
+    fn cursor_box(labyrinth: &Labyrinth, cursor: PointInGridCoordinates) -> Option<PointInGridCoordinates> {
+        labyrinth.grid.clamp(cursor)
+    }
+    // returns whether the animation goes on
+    pub fn on_animation_tick(&mut self, drawing_area: &gtk::DrawingArea, state: &mut LabyrinthState) -> Result<bool, Error> {
+        match state.labyrinth {
+            Some(ref mut labyrinth) if !state.animation.paused => {
+                labyrinth.animation_step(EventHandler::redraw(drawing_area, labyrinth.view))
+            }
+            _ => Ok(false),
+        }
+    }
+    fn draw(
+        &mut self,
+        labyrinth: &mut Labyrinth,
+        drag: Option<&ShapeDrag>,
+        cursor: Option<PointInGridCoordinates>,
+        player: Option<&Player>,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        // everything below is drawn in the coordinates of the labyrinth
         cairo_context.save();
-        labyrinth.call_for_every_box(drawing_area, |intersection, entry| -> Result<(), Error> {
-            let float_rectangle: GeneralRectangle<f64> = intersection.to()?;
-            let color = entry.state.color();
-            cairo_context.set_source_rgb(color.red(), color.green(), color.blue());
-            cairo_context.rectangle(
-                float_rectangle.x(),
-                float_rectangle.y(),
-                float_rectangle.width(),
-                float_rectangle.height(),
-            );
-            cairo_context.fill();
-            Ok(())
-        })?;
+        cairo_context.translate(labyrinth.view.offset.0, labyrinth.view.offset.1);
+        cairo_context.scale(labyrinth.view.scale, labyrinth.view.scale);
+        let renderer = self.renderer;
+        let result = renderer
+            .draw(labyrinth, cairo_context)
+            .and_then(|_| match drag {
+                Some(drag) => renderer.draw_preview(labyrinth, &drag.boxes(), drag.state, cairo_context),
+                None => Ok(()),
+            })
+            .and_then(|_| match cursor {
+                Some(cursor) => renderer.draw_cursor(labyrinth, cursor, cairo_context),
+                None => Ok(()),
+            })
+            .and_then(|_| match player {
+                Some(player) => renderer.draw_player(labyrinth, player, cairo_context),
+                None => Ok(()),
+            })
+            .and_then(|_| match player.and_then(Player::summary) {
+                Some(summary) => renderer.draw_summary(labyrinth, &summary, cairo_context),
+                None => Ok(()),
+            });
         cairo_context.restore();
-        Ok(())
+        result
     }
     fn handle_mark_box(
         &mut self,
         drawing_area: &gtk::DrawingArea,
         labyrinth: &mut Labyrinth,
-        (x, y): (f64, f64),
+        position: GeneralPointInWindowPixels<f64>,
         state: BoxState,
     ) -> Result<(), Error> {
-        labyrinth.set_box_state((x, y), state, |rectangle| -> Result<(), Error> {
-            let rectangle: GeneralRectangle<i32> = rectangle.to()?;
-            drawing_area.queue_draw_area(rectangle.x, rectangle.y, rectangle.width, rectangle.height);
-            Ok(())
-        })?;
+        labyrinth.set_box_state(position, state, EventHandler::redraw(drawing_area, labyrinth.view))?;
+        Ok(())
+    }
+    // the labyrinth reports changed areas in its own coordinates, they are
+    // redrawn where the view shows them in the window
+    fn redraw<'a>(drawing_area: &'a gtk::DrawingArea, view: View) -> impl Fn(&RectangleInBoardPixels) -> Result<(), Error> + 'a {
+        move |rectangle| EventHandler::queue_draw_rectangle(drawing_area, &view, rectangle)
+    }
+    fn queue_draw_drag(drawing_area: &gtk::DrawingArea, labyrinth: &Labyrinth, drag: &ShapeDrag) -> Result<(), Error> {
+        EventHandler::queue_draw_rectangle(drawing_area, &labyrinth.view, &labyrinth.segment_to_pixel((drag.from, drag.to)))
+    }
+    fn queue_draw_rectangle(drawing_area: &gtk::DrawingArea, view: &View, rectangle: &RectangleInBoardPixels) -> Result<(), Error> {
+        if let Some(in_window) = view.rectangle_to_window(rectangle) {
+            let in_window: GeneralRectangle<i32> = in_window.to()?;
+            drawing_area.queue_draw_area(in_window.x, in_window.y, in_window.width, in_window.height);
+        }
         Ok(())
     }
 }
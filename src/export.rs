@@ -0,0 +1,133 @@
+use cairo;
+use cairo_sys;
+
+use std::fs::File;
+use std::os::raw::{c_char, c_double};
+use std::ffi::CString;
+use std::path::Path;
+use std::str::FromStr;
+use cairo::prelude::*;
+use failure::Error;
+use rustirinth_core::basic_types::{approx_convert, LabyrinthError};
+use rustirinth_core::labyrinth::Labyrinth;
+use renderer::Renderer;
+
+// cairo-rs only wraps the image and the pdf surface
+extern "C" {
+    fn cairo_svg_surface_create(
+        filename: *const c_char,
+        width_in_points: c_double,
+        height_in_points: c_double,
+    ) -> *mut cairo_sys::cairo_surface_t;
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Png,
+    Svg,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn names() -> &'static [&'static str] {
+        &["png", "svg", "pdf"]
+    }
+    // the format belonging to the extension of the file name
+    pub fn from_path(path: &Path) -> Result<ExportFormat, Error> {
+        let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+        extension.unwrap_or_default().parse()
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+    fn from_str(name: &str) -> Result<ExportFormat, Error> {
+        match name {
+            "png" => Ok(ExportFormat::Png),
+            "svg" => Ok(ExportFormat::Svg),
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(LabyrinthError::UnknownExportFormat { name: name.to_string() }.into()),
+        }
+    }
+}
+
+// writes the labyrinth into a file, one box gets box_size * scale pixels
+// (png) or points (svg, pdf)
+pub fn export(labyrinth: &mut Labyrinth, path: &Path, format: ExportFormat, scale: f64, show_path: bool) -> Result<(), Error> {
+    write_surface(labyrinth, path, format, scale, Renderer::new(show_path))
+        .map_err(|error| error.context(format!("Could not export \"{}\"", path.display())).into())
+}
+
+fn write_surface(labyrinth: &mut Labyrinth, path: &Path, format: ExportFormat, scale: f64, renderer: Renderer) -> Result<(), Error> {
+    // half a box around the labyrinth, so the outer walls are not cut off
+    let margin = f64::from(labyrinth.grid.box_size) / 2.0;
+    let width = (f64::from(labyrinth.grid.area.width) + 2.0 * margin) * scale;
+    let height = (f64::from(labyrinth.grid.area.height) + 2.0 * margin) * scale;
+    let draw = |surface: &cairo::Surface, labyrinth: &mut Labyrinth| -> Result<(), Error> {
+        let cairo_context = cairo::Context::new(surface);
+        cairo_context.set_source_rgb(1.0, 1.0, 1.0);
+        cairo_context.paint();
+        cairo_context.scale(scale, scale);
+        cairo_context.translate(
+            margin - f64::from(labyrinth.grid.area.x),
+            margin - f64::from(labyrinth.grid.area.y),
+        );
+        renderer.draw(labyrinth, &cairo_context)?;
+        check_status(cairo_context.status())
+    };
+    match format {
+        ExportFormat::Png => {
+            let surface = cairo::ImageSurface::create(
+                cairo::Format::ARgb32,
+                approx_convert(width.ceil())?,
+                approx_convert(height.ceil())?,
+            ).map_err(status_error)?;
+            draw(&surface, labyrinth)?;
+            surface.write_to_png(&mut File::create(path)?)?;
+            Ok(())
+        }
+        ExportFormat::Svg => {
+            let filename = CString::new(path.to_string_lossy().into_owned())?;
+            // cairo returns an error surface instead of null, see check_status below
+            let surface = unsafe { cairo::Surface::from_raw_full(cairo_svg_surface_create(filename.as_ptr(), width, height)) };
+            draw(&surface, labyrinth)?;
+            surface.finish();
+            check_status(surface.status())
+        }
+        ExportFormat::Pdf => {
+            let surface = cairo::PDFSurface::create(path, width, height);
+            draw(&surface, labyrinth)?;
+            surface.finish();
+            check_status(surface.status())
+        }
+    }
+}
+
+fn check_status(status: cairo::Status) -> Result<(), Error> {
+    match status {
+        cairo::Status::Success => Ok(()),
+        _ => Err(status_error(status)),
+    }
+}
+
+pub fn status_error(status: cairo::Status) -> Error {
+    LabyrinthError::CairoFailed {
+        reason: format!("{:?}", status),
+    }.into()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(ExportFormat::from_path(Path::new("maze.png")).unwrap(), ExportFormat::Png);
+        assert_eq!(ExportFormat::from_path(Path::new("workshop/maze.SVG")).unwrap(), ExportFormat::Svg);
+        assert_eq!(ExportFormat::from_path(Path::new("maze.pdf")).unwrap(), ExportFormat::Pdf);
+        assert!(ExportFormat::from_path(Path::new("maze.txt")).is_err());
+        assert!(ExportFormat::from_path(Path::new("maze")).is_err());
+    }
+}
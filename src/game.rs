@@ -1,62 +1,106 @@
-use std;
 use gtk;
 use gdk;
 
 use std::rc::Rc;
 use std::cell::RefCell;
-use gtk::WidgetExt;
+use gtk::{HeaderBarExt, WidgetExt};
 
+use autosave;
+use error_reporter::ErrorReporter;
 use event_handler::EventHandler;
-use labyrinth::LabyrinthState;
-use main_window::LabyrinthMainWindow;
-use failure::{Error, Fail};
-use basic_types::{LabyrinthError, Rectangle};
+use state::LabyrinthState;
+use settings::Settings;
+use main_window::{self, LabyrinthMainWindow};
+use failure::Error;
+use rustirinth_core::basic_types::LabyrinthError;
+use rustirinth_core::file_format;
+use gtk_adapters;
 
 #[derive(Debug)]
 pub struct LabyrinthGame {
     main_window: LabyrinthMainWindow,
     event_handler: Rc<RefCell<EventHandler>>,
     state: Rc<RefCell<LabyrinthState>>,
+    errors: ErrorReporter,
 }
 
 impl LabyrinthGame {
-    pub fn run(box_size: u32) -> Result<(), Error> {
+    pub fn run(settings: Settings) -> Result<(), Error> {
         gtk::init()?;
-        let _ = LabyrinthGame::initialize_screen(box_size)?;
+        let game = LabyrinthGame::initialize_screen(settings)?;
         gtk::main();
-        Ok(())
-    }
-    pub fn fatal_error(error: &Error) {
-        use std::io::Write;
-        let stderr = &mut ::std::io::stderr();
-        let _ = writeln!(stderr, "Error: {}", error);
-        let mut fail: &Fail = error.cause();
-        while let Some(cause) = fail.cause() {
-            let _ = writeln!(stderr, "Caused by: {}", cause);
-            fail = cause;
-        }
-        std::process::exit(-1);
+        // a normal end, the next start has nothing to recover
+        let borrowed_state = game.state.borrow();
+        borrowed_state.autosave.discard()
     }
-    fn initialize_screen(box_size: u32) -> Result<LabyrinthGame, Error> {
+    fn initialize_screen(settings: Settings) -> Result<LabyrinthGame, Error> {
         match gdk::Screen::get_default() {
-            Some(screen) => LabyrinthGame::initialize_window(box_size, &screen),
+            Some(screen) => LabyrinthGame::initialize_window(settings, &screen),
             None => Err(LabyrinthError::CouldNotGetDefaultScreen.into()),
         }
     }
-    fn initialize_window(box_size: u32, screen: &gdk::Screen) -> Result<LabyrinthGame, Error> {
-        let main_window = LabyrinthMainWindow::new(screen)?;
+    fn initialize_window(settings: Settings, screen: &gdk::Screen) -> Result<LabyrinthGame, Error> {
+        let window_mode = settings.window_mode;
+        let mut state = LabyrinthState::new(settings)?;
+        let recovered = LabyrinthGame::recover(&mut state);
+        let board_name = main_window::board_name(state.file.as_deref());
+        let main_window = LabyrinthMainWindow::new(screen, window_mode, &board_name)?;
+        let errors = ErrorReporter::new(&main_window.info_bar, &main_window.error_label);
+        if let Err(error) = recovered {
+            errors.show(&error);
+        }
         Ok(LabyrinthGame {
             main_window,
-            event_handler: Rc::new(RefCell::new(EventHandler::new())),
-            state: Rc::new(RefCell::new(LabyrinthState::new(box_size))),
+            event_handler: Rc::new(RefCell::new(EventHandler::new(errors.clone()))),
+            errors,
+            state: Rc::new(RefCell::new(state)),
         }.connect_delete_event()
             .connect_key_press_event()
+            .connect_key_release_event()
             .connect_button_press_event()
+            .connect_button_release_event()
             .connect_motion_notify_event()
+            .connect_scroll_event()
             .connect_on_size_allocate_event()
             .connect_on_draw_event()
+            .start_autosave()
             .show_all())
     }
+    // offers the board which an earlier session left unsaved
+    fn recover(state: &mut LabyrinthState) -> Result<(), Error> {
+        let path = match state.autosave.recovery_file() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if !main_window::confirm_recovery() {
+            return state.autosave.reject(&path);
+        }
+        let labyrinth = file_format::load(&path)?;
+        state.autosave.adopt(&path)?;
+        state.dimensions = Some((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt));
+        state.loaded = Some(labyrinth);
+        // saving must not overwrite the file given with --load
+        state.file = None;
+        Ok(())
+    }
+    fn start_autosave(self) -> Self {
+        let state = self.state.clone();
+        let errors = self.errors.clone();
+        gtk::timeout_add_seconds(autosave::INTERVAL, move || {
+            // during a modal dialog a signal handler may still hold the state,
+            // the snapshot is taken next time then
+            if let Ok(mut borrowed_state) = state.try_borrow_mut() {
+                let borrowed_state = &mut *borrowed_state;
+                if let Some(ref labyrinth) = borrowed_state.labyrinth {
+                    if let Err(error) = borrowed_state.autosave.snapshot(labyrinth) {
+                        errors.handle(&error, borrowed_state);
+                    }
+                }
+            }
+            gtk::Continue(true)
+        });
+        self
+    }
     fn connect_delete_event(self) -> Self {
         self.main_window.window.connect_delete_event(|_, _| {
             gtk::main_quit();
@@ -65,25 +109,115 @@ impl LabyrinthGame {
         self
     }
     fn connect_key_press_event(self) -> Self {
-        self.main_window.window.connect_key_press_event(|_, key| {
-            if key.get_keyval() == gdk::enums::key::Escape {
+        let state = self.state.clone();
+        let event_handler = self.event_handler.clone();
+        let errors = self.errors.clone();
+        let drawing_area = self.main_window.drawing_area.clone();
+        let header_bar = self.main_window.header_bar.clone();
+        self.main_window.window.connect_key_press_event(move |window, key| {
+            // in play mode Escape goes back to editing
+            let playing = state.borrow().player.is_some();
+            if key.get_keyval() == gdk::enums::key::Escape && !playing {
                 gtk::main_quit();
+            } else if let Some(operation) = EventHandler::file_operation(key) {
+                let file = state.borrow().file.clone();
+                if let Some(path) = main_window::choose_file(window, operation, file.as_deref()) {
+                    let mut borrowed_state = state.borrow_mut();
+                    let result = event_handler
+                        .borrow_mut()
+                        .on_file_chosen(&drawing_area, &mut *borrowed_state, operation, &path);
+                    if let Err(error) = result {
+                        errors.handle(&error, &borrowed_state);
+                    }
+                    drop(borrowed_state);
+                    if let Some(ref header_bar) = header_bar {
+                        header_bar.set_subtitle(main_window::board_name(state.borrow().file.as_deref()).as_str());
+                    }
+                }
+            } else {
+                {
+                    let mut borrowed_state = state.borrow_mut();
+                    let result = event_handler.borrow_mut().on_key_press(&drawing_area, &mut *borrowed_state, key);
+                    if let Err(error) = result {
+                        errors.handle(&error, &borrowed_state);
+                    }
+                }
+                LabyrinthGame::schedule_animation(state.clone(), event_handler.clone(), drawing_area.clone(), errors.clone());
             }
             gtk::Inhibit(true)
         });
         self
     }
+    fn connect_key_release_event(self) -> Self {
+        let state = self.state.clone();
+        let event_handler = self.event_handler.clone();
+        self.main_window.window.connect_key_release_event(move |_, key| {
+            event_handler.borrow_mut().on_key_release(&mut *state.borrow_mut(), key);
+            gtk::Inhibit(true)
+        });
+        self
+    }
+    fn schedule_animation(
+        state: Rc<RefCell<LabyrinthState>>,
+        event_handler: Rc<RefCell<EventHandler>>,
+        drawing_area: Rc<gtk::DrawingArea>,
+        errors: ErrorReporter,
+    ) {
+        let interval = {
+            let mut borrowed_state = state.borrow_mut();
+            if !borrowed_state.needs_animation_timer() {
+                return;
+            }
+            borrowed_state.animation.timer_active = true;
+            borrowed_state.animation.interval
+        };
+        gtk::timeout_add(interval, move || {
+            let mut borrowed_state = state.borrow_mut();
+            if borrowed_state.animation.interval != interval {
+                // the speed was changed: go on with a new timer
+                borrowed_state.animation.timer_active = false;
+                drop(borrowed_state);
+                LabyrinthGame::schedule_animation(state.clone(), event_handler.clone(), drawing_area.clone(), errors.clone());
+                return gtk::Continue(false);
+            }
+            // the animation stops at an error, the board stays usable
+            let result = event_handler.borrow_mut().on_animation_tick(&drawing_area, &mut *borrowed_state);
+            let running = result.unwrap_or_else(|error| {
+                errors.handle(&error, &borrowed_state);
+                false
+            });
+            borrowed_state.animation.timer_active = running;
+            gtk::Continue(running)
+        });
+    }
     fn connect_button_press_event(self) -> Self {
         let state = self.state.clone();
         let event_handler = self.event_handler.clone();
+        let errors = self.errors.clone();
         self.main_window
             .drawing_area
             .connect_button_press_event(move |drawing_area, event| {
                 let mut borrowed_state = state.borrow_mut();
-                event_handler
-                    .borrow_mut()
-                    .on_button_press(drawing_area, &mut *borrowed_state, event)
-                    .unwrap_or_else(|e| LabyrinthGame::fatal_error(&e));
+                let result = event_handler.borrow_mut().on_button_press(drawing_area, &mut *borrowed_state, event);
+                if let Err(error) = result {
+                    errors.handle(&error, &borrowed_state);
+                }
+                gtk::Inhibit(true)
+            });
+        self
+    }
+    fn connect_button_release_event(self) -> Self {
+        let state = self.state.clone();
+        let event_handler = self.event_handler.clone();
+        let errors = self.errors.clone();
+        self.main_window
+            .drawing_area
+            .connect_button_release_event(move |drawing_area, event| {
+                let mut borrowed_state = state.borrow_mut();
+                let result = event_handler.borrow_mut().on_button_release(drawing_area, &mut *borrowed_state, event);
+                if let Err(error) = result {
+                    errors.handle(&error, &borrowed_state);
+                }
                 gtk::Inhibit(true)
             });
         self
@@ -91,32 +225,38 @@ impl LabyrinthGame {
     fn connect_on_size_allocate_event(self) -> Self {
         let state = self.state.clone();
         let event_handler = self.event_handler.clone();
+        let errors = self.errors.clone();
         self.main_window
             .drawing_area
             .connect_size_allocate(move |_, rect| {
-                let rectangle = Rectangle::from(rect).unwrap_or_else(|e| {
-                    LabyrinthGame::fatal_error(&e);
-                    Rectangle::default()
-                });
                 let mut borrowed_state = state.borrow_mut();
-                event_handler
-                    .borrow_mut()
-                    .on_size_allocate(&mut *borrowed_state, &rectangle)
-                    .unwrap_or_else(|e| LabyrinthGame::fatal_error(&e));
+                let rectangle = match gtk_adapters::rectangle_from_gtk(rect) {
+                    Ok(rectangle) => rectangle,
+                    Err(error) => return errors.handle(&error, &borrowed_state),
+                };
+                let result = event_handler.borrow_mut().on_size_allocate(&mut *borrowed_state, &rectangle);
+                if let Err(error) = result {
+                    errors.handle(&error, &borrowed_state);
+                }
             });
         self
     }
     fn connect_on_draw_event(self) -> Self {
         let event_handler = self.event_handler.clone();
         let state = self.state.clone();
+        let errors = self.errors.clone();
+        let status_label = self.main_window.status_label.clone();
         self.main_window
             .drawing_area
             .connect_draw(move |_, cairo_context| {
                 let mut borrowed_state = state.borrow_mut();
-                event_handler
-                    .borrow_mut()
-                    .on_draw(&mut *borrowed_state, cairo_context)
-                    .unwrap_or_else(|e| LabyrinthGame::fatal_error(&e));
+                let result = event_handler.borrow_mut().on_draw(&mut *borrowed_state, cairo_context);
+                if let Err(error) = result {
+                    errors.handle(&error, &borrowed_state);
+                }
+                // every change of the solution redraws the board
+                let solution = borrowed_state.labyrinth.as_ref().and_then(|labyrinth| labyrinth.solution.as_ref());
+                main_window::show_solution(&status_label, solution);
                 gtk::Inhibit(true)
             });
         self
@@ -124,14 +264,31 @@ impl LabyrinthGame {
     fn connect_motion_notify_event(self) -> Self {
         let event_handler = self.event_handler.clone();
         let state = self.state.clone();
+        let errors = self.errors.clone();
         self.main_window
             .drawing_area
             .connect_motion_notify_event(move |drawing_area, event| {
                 let mut borrowed_state = state.borrow_mut();
-                event_handler
-                    .borrow_mut()
-                    .on_motion_notify(drawing_area, &mut *borrowed_state, event)
-                    .unwrap_or_else(|e| LabyrinthGame::fatal_error(&e));
+                let result = event_handler.borrow_mut().on_motion_notify(drawing_area, &mut *borrowed_state, event);
+                if let Err(error) = result {
+                    errors.handle(&error, &borrowed_state);
+                }
+                gtk::Inhibit(true)
+            });
+        self
+    }
+    fn connect_scroll_event(self) -> Self {
+        let event_handler = self.event_handler.clone();
+        let state = self.state.clone();
+        let errors = self.errors.clone();
+        self.main_window
+            .drawing_area
+            .connect_scroll_event(move |drawing_area, event| {
+                let mut borrowed_state = state.borrow_mut();
+                let result = event_handler.borrow_mut().on_scroll(drawing_area, &mut *borrowed_state, event);
+                if let Err(error) = result {
+                    errors.handle(&error, &borrowed_state);
+                }
                 gtk::Inhibit(true)
             });
         self
@@ -0,0 +1,60 @@
+use gtk;
+
+use failure::Error;
+use rustirinth_core::basic_types::Rectangle;
+use rustirinth_core::grid::GeneralPointInWindowPixels;
+
+// rustirinth-core does not know about gtk, so its rectangles are converted here
+pub fn rectangle_from_gtk(rectangle: &gtk::Rectangle) -> Result<Rectangle, Error> {
+    Rectangle::from(&(rectangle.x, rectangle.y, rectangle.width, rectangle.height))
+}
+
+// the position of a mouse event in the drawing area
+pub fn point_from_gdk((x, y): (f64, f64)) -> GeneralPointInWindowPixels<f64> {
+    GeneralPointInWindowPixels::new(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use gtk;
+    use super::*;
+
+    #[test]
+    fn from_gtk_err() {
+        let gtk_rectangle = gtk::Rectangle {
+            x: -1,
+            y: 2,
+            width: 3,
+            height: 4,
+        };
+        let rectangle = rectangle_from_gtk(&gtk_rectangle);
+        assert!(rectangle.is_err());
+        let error = rectangle.err().unwrap();
+        let error_string = format!("{}", error);
+        assert_eq!(
+            error_string,
+            "Conversion error or overflow while converting \"-1\""
+        );
+    }
+
+    #[test]
+    fn from_gtk_ok() {
+        let gtk_rectangle = gtk::Rectangle {
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 4,
+        };
+        let rectangle = rectangle_from_gtk(&gtk_rectangle).unwrap();
+        assert_eq!(
+            rectangle,
+            Rectangle {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+            }
+        );
+    }
+}
@@ -1,24 +1,80 @@
 extern crate cairo;
+extern crate cairo_sys;
 #[macro_use]
 extern crate clap;
 extern crate conv;
 extern crate failure;
-#[macro_use]
-extern crate failure_derive;
 extern crate gdk;
 extern crate gtk;
 extern crate ndarray;
+extern crate rand;
+extern crate rustirinth_core;
 
 mod main_window;
+mod autosave;
 mod game;
+mod error_reporter;
 mod event_handler;
-mod labyrinth;
-mod basic_types;
-mod rectangle;
-mod grid;
+mod export;
+mod gtk_adapters;
+mod png_import;
+mod renderer;
+mod settings;
+mod state;
+
+use std::cmp::max;
+use rustirinth_core::{basic_types, file_format, generator, grid, image_import, labyrinth, solver};
+
+const DEFAULT_BOX_SIZE: &str = "64";
+const DEFAULT_HEURISTIC: &str = "manhattan";
+const DEFAULT_NEIGHBOURHOOD: &str = "4";
+const DEFAULT_CORNER_CUTTING: &str = "no-squeezing";
+const DEFAULT_GENERATOR: &str = "backtracker";
+const DEFAULT_SCALE: &str = "1";
+const DEFAULT_THRESHOLD: &str = "0.5";
+
+// the options of the shortest way search, used by the game and by "solve"
+fn search_arguments<'a, 'b>() -> Vec<clap::Arg<'a, 'b>> {
+    vec![
+        clap::Arg::with_name("neighbourhood")
+            .long("neighbourhood")
+            .short("n")
+            .default_value(DEFAULT_NEIGHBOURHOOD)
+            .help("Whether the way may only go straight (4) or also diagonally (8)")
+            .possible_values(solver::Neighbourhood::names()),
+        clap::Arg::with_name("corner-cutting")
+            .long("corner-cutting")
+            .default_value(DEFAULT_CORNER_CUTTING)
+            .help("Whether a diagonal step may pass the corner of a wall")
+            .possible_values(solver::CornerCutting::names()),
+        clap::Arg::with_name("heuristic")
+            .long("heuristic")
+            .default_value(DEFAULT_HEURISTIC)
+            .help("The heuristic used by the A* search for the shortest way")
+            .possible_values(solver::HeuristicKind::names()),
+    ]
+}
+
+fn search_options(args: &clap::ArgMatches) -> Result<solver::SearchOptions, failure::Error> {
+    let heuristic = args.value_of("heuristic")
+        .unwrap_or(DEFAULT_HEURISTIC)
+        .parse::<solver::HeuristicKind>()?;
+    let neighbourhood = args.value_of("neighbourhood")
+        .unwrap_or(DEFAULT_NEIGHBOURHOOD)
+        .parse::<solver::Neighbourhood>()?;
+    let corner_cutting = args.value_of("corner-cutting")
+        .unwrap_or(DEFAULT_CORNER_CUTTING)
+        .parse::<solver::CornerCutting>()?;
+    Ok(solver::SearchOptions {
+        heuristic,
+        movement: solver::Movement {
+            neighbourhood,
+            corner_cutting,
+        },
+    })
+}
 
 fn run() -> Result<(), failure::Error> {
-    let default_box_size = "64";
     let args = clap::App::new("Rustirinth")
         .about("A simple labyrinth game")
         .author(crate_authors!())
@@ -27,19 +83,243 @@ fn run() -> Result<(), failure::Error> {
             clap::Arg::with_name("box-size")
                 .long("box-size")
                 .short("s")
-                .default_value(default_box_size)
-                .help("The size of the boxes on the screen")
-                .possible_values(&["16", "32", "64", "128"]),
+                .default_value(DEFAULT_BOX_SIZE)
+                .help("The box size at the initial zoom level, without --columns and --rows it sets the number of boxes"),
+        )
+        .arg(
+            clap::Arg::with_name("windowed")
+                .long("windowed")
+                .short("w")
+                .help("Opens a normal window instead of filling the whole screen"),
+        )
+        .arg(
+            clap::Arg::with_name("geometry")
+                .long("geometry")
+                .takes_value(true)
+                .value_name("WIDTHxHEIGHT")
+                .help("The initial size of the window, implies --windowed"),
+        )
+        .arg(
+            clap::Arg::with_name("columns")
+                .long("columns")
+                .takes_value(true)
+                .requires("rows")
+                .help("A fixed number of boxes in a row, the size of the boxes is adapted to the window"),
+        )
+        .arg(
+            clap::Arg::with_name("rows")
+                .long("rows")
+                .takes_value(true)
+                .requires("columns")
+                .help("A fixed number of boxes in a column, the size of the boxes is adapted to the window"),
+        )
+        .args(&search_arguments())
+        .arg(
+            clap::Arg::with_name("generator")
+                .long("generator")
+                .default_value(DEFAULT_GENERATOR)
+                .help("The algorithm which generates a random maze")
+                .possible_values(generator::GeneratorKind::names()),
+        )
+        .arg(
+            clap::Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("Seed for the maze generator, the same seed always generates the same maze"),
+        )
+        .arg(
+            clap::Arg::with_name("load")
+                .long("load")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Loads a labyrinth saved with Ctrl+S"),
+        )
+        .arg(
+            clap::Arg::with_name("import")
+                .long("import")
+                .takes_value(true)
+                .value_name("PNG")
+                .conflicts_with("load")
+                .help("Turns an image into a labyrinth, --columns and --rows set the number of boxes"),
+        )
+        .arg(
+            clap::Arg::with_name("threshold")
+                .long("threshold")
+                .default_value(DEFAULT_THRESHOLD)
+                .help("Imported boxes darker than this become walls, from 0 (black) to 1 (white)"),
+        )
+        .arg(
+            clap::Arg::with_name("no-markers")
+                .long("no-markers")
+                .help("Imports green and red pixels like any other color instead of as start and end"),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("solve")
+                .about("Prints the shortest way through a saved labyrinth without opening a window")
+                .arg(
+                    clap::Arg::with_name("file")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("The labyrinth file"),
+                )
+                .args(&search_arguments()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("export")
+                .about("Draws a saved labyrinth into a png, svg or pdf file without opening a window")
+                .arg(
+                    clap::Arg::with_name("file")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("The labyrinth file"),
+                )
+                .arg(
+                    clap::Arg::with_name("output")
+                        .required(true)
+                        .value_name("OUTPUT")
+                        .help("The image or document to write"),
+                )
+                .arg(
+                    clap::Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .help("The format of the output, by default taken from its extension")
+                        .possible_values(export::ExportFormat::names()),
+                )
+                .arg(
+                    clap::Arg::with_name("scale")
+                        .long("scale")
+                        .default_value(DEFAULT_SCALE)
+                        .help("Multiplies the box size of the file, in pixels for png and points for svg and pdf"),
+                )
+                .arg(
+                    clap::Arg::with_name("no-path")
+                        .long("no-path")
+                        .help("Leaves out the shortest way, e.g. for a printed maze"),
+                )
+                .args(&search_arguments()),
         )
         .get_matches();
+    if let Some(solve_args) = args.subcommand_matches("solve") {
+        return solve(solve_args);
+    }
+    if let Some(export_args) = args.subcommand_matches("export") {
+        return export(export_args);
+    }
     let box_size = args.value_of("box-size")
-        .unwrap_or(default_box_size)
+        .unwrap_or(DEFAULT_BOX_SIZE)
         .parse::<u32>()?;
-    game::LabyrinthGame::run(box_size)
+    if box_size < labyrinth::Labyrinth::MINIMUM_BOX_SIZE {
+        return Err(basic_types::LabyrinthError::BoxSizeTooSmall {
+            box_size,
+            minimum: labyrinth::Labyrinth::MINIMUM_BOX_SIZE,
+        }.into());
+    }
+    let dimensions = match (args.value_of("columns"), args.value_of("rows")) {
+        (Some(columns), Some(rows)) => {
+            let dimensions = (columns.parse::<u32>()?, rows.parse::<u32>()?);
+            if dimensions.0 == 0 || dimensions.1 == 0 {
+                return Err(basic_types::LabyrinthError::EmptyBoard.into());
+            }
+            let box_cnt = max(dimensions.0, dimensions.1);
+            if box_cnt > file_format::MAXIMUM_BOX_COUNT {
+                return Err(basic_types::LabyrinthError::TooManyBoxes {
+                    box_cnt,
+                    maximum: file_format::MAXIMUM_BOX_COUNT,
+                }.into());
+            }
+            Some(dimensions)
+        }
+        _ => None,
+    };
+    // the board has to fit into the pixel coordinates, without fixed
+    // dimensions the boxes are never larger than the window
+    let box_cnt = dimensions.map_or(1, |(columns, rows)| max(columns, rows));
+    if grid::Grid::pixel_size(box_cnt, box_size).is_none() {
+        return Err(basic_types::LabyrinthError::BoxSizeTooLarge {
+            box_size,
+            maximum: (u32::MAX - 1) / box_cnt,
+        }.into());
+    }
+    let window_mode = match args.value_of("geometry") {
+        Some(geometry) => geometry.parse::<settings::WindowMode>()?,
+        None if args.is_present("windowed") => settings::WindowMode::DEFAULT_WINDOWED,
+        None => settings::WindowMode::Fullscreen,
+    };
+    let generator = args.value_of("generator")
+        .unwrap_or(DEFAULT_GENERATOR)
+        .parse::<generator::GeneratorKind>()?;
+    let seed = match args.value_of("seed") {
+        Some(seed) => Some(seed.parse::<usize>()?),
+        None => None,
+    };
+    let threshold_value = args.value_of("threshold").unwrap_or(DEFAULT_THRESHOLD);
+    let threshold = threshold_value.parse::<f64>()?;
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(basic_types::LabyrinthError::InvalidThreshold {
+            value: threshold_value.to_string(),
+        }.into());
+    }
+    let import_options = image_import::ImportOptions {
+        box_count: dimensions,
+        threshold,
+        markers: !args.is_present("no-markers"),
+    };
+    game::LabyrinthGame::run(settings::Settings {
+        box_size,
+        dimensions,
+        window_mode,
+        search_options: search_options(&args)?,
+        generator,
+        seed,
+        load: args.value_of_os("load").map(std::path::PathBuf::from),
+        import: args.value_of_os("import").map(std::path::PathBuf::from),
+        import_options,
+    })
+}
+
+// runs without gtk, so it can be used in scripts
+fn solve(args: &clap::ArgMatches) -> Result<(), failure::Error> {
+    let path = std::path::Path::new(args.value_of_os("file").unwrap_or_default());
+    let mut labyrinth = file_format::load(path)?;
+    labyrinth.set_search_options(search_options(args)?, |_| Ok(()))?;
+    if labyrinth.start.is_none() || labyrinth.end.is_none() {
+        return Err(basic_types::LabyrinthError::MissingEndpoint.into());
+    }
+    match labyrinth.solution {
+        Some(ref solution) => {
+            println!("length: {}", solution.path.len() - 1);
+            println!("cost: {}", solution.cost);
+            println!("expanded: {}", solution.expanded);
+            print!("{}", file_format::render(&labyrinth, &solution.path));
+            Ok(())
+        }
+        None => Err(basic_types::LabyrinthError::NoPath.into()),
+    }
+}
+
+// runs without gtk as well, cairo does not need a display
+fn export(args: &clap::ArgMatches) -> Result<(), failure::Error> {
+    let path = std::path::Path::new(args.value_of_os("file").unwrap_or_default());
+    let output = std::path::Path::new(args.value_of_os("output").unwrap_or_default());
+    let format = match args.value_of("format") {
+        Some(format) => format.parse::<export::ExportFormat>()?,
+        None => export::ExportFormat::from_path(output)?,
+    };
+    let scale_value = args.value_of("scale").unwrap_or(DEFAULT_SCALE);
+    let scale = scale_value.parse::<f64>()?;
+    if !(scale > 0.0 && scale.is_finite()) {
+        return Err(basic_types::LabyrinthError::InvalidScale {
+            value: scale_value.to_string(),
+        }.into());
+    }
+    let mut labyrinth = file_format::load(path)?;
+    labyrinth.set_search_options(search_options(args)?, |_| Ok(()))?;
+    export::export(&mut labyrinth, output, format, scale, !args.is_present("no-path"))
 }
 
 fn main() {
     if let Err(ref e) = run() {
-        game::LabyrinthGame::fatal_error(e);
+        error_reporter::fatal_error(e);
     }
 }
@@ -3,37 +3,170 @@ use gtk;
 use gdk;
 use failure;
 
-use basic_types;
+use rustirinth_core::basic_types;
+use rustirinth_core::solver::Solution;
+use event_handler::FileOperation;
+use settings::WindowMode;
 
 #[derive(Debug)]
 pub struct LabyrinthMainWindow {
     pub window: gtk::Window,
     pub drawing_area: std::rc::Rc<gtk::DrawingArea>,
+    // only in windowed mode, shows the name of the board
+    pub header_bar: Option<gtk::HeaderBar>,
+    // over the top of the board, hidden until an error is shown in it
+    pub info_bar: gtk::InfoBar,
+    pub error_label: gtk::Label,
+    // in the bottom left corner of the board: length, cost and search effort
+    // of the shortest way
+    pub status_label: gtk::Label,
     pub requested_size: (u32, u32),
 }
 
 impl LabyrinthMainWindow {
-    pub fn new(screen: &gdk::Screen) -> Result<LabyrinthMainWindow, failure::Error> {
+    pub fn new(screen: &gdk::Screen, window_mode: WindowMode, board_name: &str) -> Result<LabyrinthMainWindow, failure::Error> {
         use gtk::prelude::*;
         use gdk::ScreenExt;
         let event_mask: i32 = (gdk::EventMask::POINTER_MOTION_MASK.bits() | gdk::EventMask::POINTER_MOTION_HINT_MASK.bits()
-            | gdk::EventMask::BUTTON_PRESS_MASK.bits()) as i32;
+            | gdk::EventMask::BUTTON_PRESS_MASK.bits() | gdk::EventMask::BUTTON_RELEASE_MASK.bits()
+            | gdk::EventMask::SCROLL_MASK.bits()) as i32;
         let window = gtk::Window::new(gtk::WindowType::Toplevel);
-        let monitor = screen.get_primary_monitor();
-        let monitor_workarea = screen.get_monitor_workarea(monitor);
-        window.fullscreen_on_monitor(screen, monitor);
+        window.set_title("Rustirinth");
         let drawing_area = gtk::DrawingArea::new();
-        drawing_area.set_size_request(monitor_workarea.width, monitor_workarea.height);
-        window.add(&drawing_area);
+        let (requested_width, requested_height, header_bar) = match window_mode {
+            WindowMode::Fullscreen => {
+                let monitor = screen.get_primary_monitor();
+                let monitor_workarea = screen.get_monitor_workarea(monitor);
+                window.fullscreen_on_monitor(screen, monitor);
+                drawing_area.set_size_request(monitor_workarea.width, monitor_workarea.height);
+                (monitor_workarea.width, monitor_workarea.height, None)
+            }
+            WindowMode::Windowed { width, height } => {
+                // the drawing area follows the size of the window, every change
+                // arrives through connect_size_allocate
+                let width = basic_types::convert(width)?;
+                let height = basic_types::convert(height)?;
+                let header_bar = gtk::HeaderBar::new();
+                header_bar.set_title("Rustirinth");
+                header_bar.set_subtitle(board_name);
+                header_bar.set_show_close_button(true);
+                window.set_titlebar(&header_bar);
+                window.set_default_size(width, height);
+                (width, height, Some(header_bar))
+            }
+        };
+        let (info_bar, error_label) = LabyrinthMainWindow::new_info_bar();
+        // the bar covers the board instead of taking space from it, so
+        // showing or hiding it does not resize the board
+        let overlay = gtk::Overlay::new();
+        overlay.add(&drawing_area);
+        info_bar.set_halign(gtk::Align::Fill);
+        info_bar.set_valign(gtk::Align::Start);
+        overlay.add_overlay(&info_bar);
+        let status_label = gtk::Label::new(None);
+        status_label.set_halign(gtk::Align::Start);
+        status_label.set_valign(gtk::Align::End);
+        status_label.set_margin_start(8);
+        status_label.set_margin_bottom(8);
+        overlay.add_overlay(&status_label);
+        window.add(&overlay);
         drawing_area.set_can_default(true);
         drawing_area.grab_default();
         drawing_area.add_events(event_mask);
-        let requested_width = basic_types::convert(monitor_workarea.width)?;
-        let requested_height = basic_types::convert(monitor_workarea.height)?;
+        let requested_width = basic_types::convert(requested_width)?;
+        let requested_height = basic_types::convert(requested_height)?;
         Ok(LabyrinthMainWindow {
             window,
             drawing_area: std::rc::Rc::new(drawing_area),
+            header_bar,
+            info_bar,
+            error_label,
+            status_label,
             requested_size: (requested_width, requested_height),
         })
     }
+    fn new_info_bar() -> (gtk::InfoBar, gtk::Label) {
+        use gtk::prelude::*;
+        let info_bar = gtk::InfoBar::new();
+        let error_label = gtk::Label::new(None);
+        error_label.set_line_wrap(true);
+        error_label.set_selectable(true);
+        if let Some(content_area) = info_bar.get_content_area().and_then(|area| area.downcast::<gtk::Container>().ok()) {
+            content_area.add(&error_label);
+        }
+        info_bar.set_show_close_button(true);
+        info_bar.connect_response(|info_bar, _| info_bar.hide());
+        // show_all on the window must not show it
+        info_bar.set_no_show_all(true);
+        (info_bar, error_label)
+    }
+}
+
+// the file name of the board, shown in the header bar
+pub fn board_name(file: Option<&std::path::Path>) -> String {
+    match file.and_then(|file| file.file_name()) {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => "Untitled".to_string(),
+    }
+}
+
+// the label is only touched when the text changes, as this runs on every redraw
+pub fn show_solution(status_label: &gtk::Label, solution: Option<&Solution>) {
+    use gtk::prelude::*;
+    let status = solution.map(Solution::summary).unwrap_or_default();
+    if status_label.get_text().unwrap_or_default() != status {
+        status_label.set_text(&status);
+    }
+}
+
+// asks whether the board of an earlier session, which did not end
+// normally, should be restored
+pub fn confirm_recovery() -> bool {
+    use gtk::prelude::*;
+    let dialog = gtk::MessageDialog::new(
+        None::<&gtk::Window>,
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        gtk::ButtonsType::YesNo,
+        "Restore the unsaved board?",
+    );
+    dialog.set_property_secondary_text(Some(
+        "An earlier game did not end normally. The board is lost if it is not restored now.",
+    ));
+    let yes: i32 = gtk::ResponseType::Yes.into();
+    let restore = dialog.run() == yes;
+    dialog.destroy();
+    restore
+}
+
+// shows a modal file chooser, returns None if the user cancelled it
+pub fn choose_file(parent: &gtk::Window, operation: FileOperation, file: Option<&std::path::Path>) -> Option<std::path::PathBuf> {
+    use gtk::prelude::*;
+    let (title, accept, action) = match operation {
+        FileOperation::Save => ("Save labyrinth", "_Save", gtk::FileChooserAction::Save),
+        FileOperation::Open => ("Open labyrinth", "_Open", gtk::FileChooserAction::Open),
+        FileOperation::Export => ("Export labyrinth as png, svg or pdf", "_Export", gtk::FileChooserAction::Save),
+        FileOperation::Import => ("Import labyrinth from a png image", "_Import", gtk::FileChooserAction::Open),
+    };
+    let dialog = gtk::FileChooserDialog::new(Some(title), Some(parent), action);
+    dialog.add_button("_Cancel", gtk::ResponseType::Cancel.into());
+    dialog.add_button(accept, gtk::ResponseType::Accept.into());
+    dialog.set_do_overwrite_confirmation(true);
+    match (operation, file) {
+        (FileOperation::Export, _) => dialog.set_current_name("labyrinth.png"),
+        (FileOperation::Import, _) => {}
+        (_, Some(file)) => {
+            dialog.set_filename(file);
+        }
+        (FileOperation::Save, None) => dialog.set_current_name("labyrinth.txt"),
+        (_, None) => {}
+    }
+    let accepted: i32 = gtk::ResponseType::Accept.into();
+    let filename = if dialog.run() == accepted {
+        dialog.get_filename()
+    } else {
+        None
+    };
+    dialog.destroy();
+    filename
 }
@@ -0,0 +1,45 @@
+use cairo;
+
+use std::fs::File;
+use std::path::Path;
+use failure::Error;
+use rustirinth_core::basic_types::convert;
+use rustirinth_core::image_import::{self, Bitmap, ImportOptions};
+use rustirinth_core::labyrinth::Labyrinth;
+use export;
+
+// turns a png into a labyrinth, dark pixels become walls
+pub fn load(path: &Path, box_size: u32, options: &ImportOptions) -> Result<Labyrinth, Error> {
+    read_bitmap(path)
+        .and_then(|bitmap| image_import::import(&bitmap, box_size, options))
+        .map_err(|error| error.context(format!("Could not import \"{}\"", path.display())).into())
+}
+
+fn read_bitmap(path: &Path) -> Result<Bitmap, Error> {
+    let image = cairo::ImageSurface::create_from_png(&mut File::open(path)?)?;
+    let (width, height) = (image.get_width(), image.get_height());
+    // cairo loads pngs in different formats, painting them onto a white
+    // surface gives the same pixel layout for all and removes transparency
+    let mut surface = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)
+        .map_err(export::status_error)?;
+    {
+        let cairo_context = cairo::Context::new(&surface);
+        cairo_context.set_source_rgb(1.0, 1.0, 1.0);
+        cairo_context.paint();
+        cairo_context.set_source_surface(&image, 0.0, 0.0);
+        cairo_context.paint();
+    }
+    let stride: usize = convert(surface.get_stride())?;
+    let (width, height): (u32, u32) = (convert(width)?, convert(height)?);
+    let data = surface.get_data()?;
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            // one native endian u32 per pixel: alpha, red, green, blue
+            let offset = y * stride + 4 * x;
+            let value = u32::from_ne_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+            pixels.push(((value >> 16) as u8, (value >> 8) as u8, value as u8));
+        }
+    }
+    Bitmap::new(width, height, pixels)
+}
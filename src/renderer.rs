@@ -0,0 +1,289 @@
+use cairo;
+
+use std::cmp::{max, min};
+use std::f64::consts::PI;
+use rustirinth_core::basic_types::{Color, GeneralRectangle, IsAColor, IsARectangle, IsARectangularArea, Rectangle};
+use rustirinth_core::grid::{PointInGridCoordinates, RectangleInBoardPixels};
+use rustirinth_core::labyrinth::{BoxState, Labyrinth};
+use rustirinth_core::play::Player;
+use failure::Error;
+
+// draws the labyrinth with cairo, used for the window as well as for exports
+#[derive(Debug, Copy, Clone)]
+pub struct Renderer {
+    // the shortest way between start and end
+    pub show_path: bool,
+}
+
+impl Renderer {
+    pub fn new(show_path: bool) -> Renderer {
+        Renderer { show_path }
+    }
+    // draws the part of the labyrinth inside the clip area, in the coordinates
+    // of the labyrinth, the context may be scaled or translated
+    pub fn draw(&self, labyrinth: &mut Labyrinth, cairo_context: &cairo::Context) -> Result<(), Error> {
+        let (top_left_x, top_left_y, bottom_right_x, bottom_right_y) = cairo_context.clip_extents();
+        // panned to the right or down, the clip area starts left of or above the labyrinth
+        let (top_left_x, top_left_y) = (top_left_x.max(0.0), top_left_y.max(0.0));
+        if bottom_right_x <= top_left_x || bottom_right_y <= top_left_y {
+            return Ok(());
+        }
+        let draw_area = Rectangle::approx_from(&(
+            top_left_x,
+            top_left_y,
+            bottom_right_x - top_left_x,
+            bottom_right_y - top_left_y,
+        ))?;
+        if let Some(intersection) = draw_area.intersect(&labyrinth.grid.area) {
+            let intersection = RectangleInBoardPixels::new(intersection);
+            self.draw_axes(&intersection, labyrinth, cairo_context)?;
+            self.draw_boxes(&intersection, labyrinth, cairo_context)?;
+            if self.show_path {
+                self.draw_path(&intersection, labyrinth, cairo_context)?;
+            }
+            // self.draw_legend(&intersection, labyrinth, cairo_context)?;
+        }
+        Ok(())
+    }
+    fn draw_axes(
+        &self,
+        draw_area: &RectangleInBoardPixels,
+        labyrinth: &Labyrinth,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        cairo_context.save();
+        Renderer::set_source_color(cairo_context, &Color::get_black());
+
+        self.draw_axes_x(draw_area, labyrinth, cairo_context)?;
+        self.draw_axes_y(draw_area, labyrinth, cairo_context)?;
+
+        cairo_context.stroke();
+        cairo_context.restore();
+        Ok(())
+    }
+    fn draw_axes_x(
+        &self,
+        draw_area: &RectangleInBoardPixels,
+        labyrinth: &Labyrinth,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        let grid = &labyrinth.grid;
+        let start_x_cnt = (draw_area.top_left_x() - grid.area.x + grid.box_size - 1) / grid.box_size;
+        let end_x_cnt = min(
+            grid.x_box_cnt + 1,
+            (draw_area.bottom_right_x() - grid.area.x + grid.box_size - 1) / grid.box_size,
+        );
+
+        for x_cnt in start_x_cnt..end_x_cnt {
+            let start_x = grid.area.x + grid.box_size * x_cnt;
+            let start_y = max(grid.area.y, draw_area.top_left_y());
+            self.draw_line(
+                RectangleInBoardPixels::new(Rectangle {
+                    x: start_x,
+                    y: start_y,
+                    width: 0,
+                    height: grid.area.height,
+                }),
+                draw_area,
+                cairo_context,
+            )?;
+        }
+        Ok(())
+    }
+    fn draw_axes_y(
+        &self,
+        draw_area: &RectangleInBoardPixels,
+        labyrinth: &Labyrinth,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        let grid = &labyrinth.grid;
+        let start_y_cnt = (draw_area.top_left_y() - grid.area.y + grid.box_size - 1) / grid.box_size;
+        let end_y_cnt = min(
+            grid.y_box_cnt + 1,
+            (draw_area.bottom_right_y() - grid.area.y + grid.box_size - 1) / grid.box_size,
+        );
+
+        for y_cnt in start_y_cnt..end_y_cnt {
+            let start_x = max(grid.area.x, draw_area.top_left_x());
+            let start_y = grid.area.y + grid.box_size * y_cnt;
+            self.draw_line(
+                RectangleInBoardPixels::new(Rectangle {
+                    x: start_x,
+                    y: start_y,
+                    width: grid.area.width,
+                    height: 0,
+                }),
+                draw_area,
+                cairo_context,
+            )?;
+        }
+        Ok(())
+    }
+    fn draw_line(
+        &self,
+        line: RectangleInBoardPixels,
+        draw_area: &RectangleInBoardPixels,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        match draw_area
+            .intersect(&line)
+            .map(|x| x.approx_to::<f64, GeneralRectangle<f64>>())
+        {
+            Some(Ok(intersection)) => {
+                cairo_context.move_to(intersection.top_left_x(), intersection.top_left_y());
+                cairo_context.line_to(intersection.bottom_right_x(), intersection.bottom_right_y());
+                Ok(())
+            }
+            Some(Err(err)) => Err(err),
+            None => Ok(()),
+        }
+    }
+    fn draw_boxes(
+        &self,
+        drawing_area: &RectangleInBoardPixels,
+        labyrinth: &mut Labyrinth,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        cairo_context.save();
+        labyrinth.call_for_every_box(drawing_area, |intersection, entry| -> Result<(), Error> {
+            let float_rectangle: GeneralRectangle<f64> = intersection.to()?;
+            Renderer::set_source_color(cairo_context, &entry.color());
+            cairo_context.rectangle(
+                float_rectangle.x(),
+                float_rectangle.y(),
+                float_rectangle.width(),
+                float_rectangle.height(),
+            );
+            cairo_context.fill();
+            Ok(())
+        })?;
+        cairo_context.restore();
+        Ok(())
+    }
+    fn draw_path(
+        &self,
+        draw_area: &RectangleInBoardPixels,
+        labyrinth: &Labyrinth,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        cairo_context.save();
+        Renderer::set_source_color(cairo_context, &Color::get_black());
+        cairo_context.set_line_width(f64::from(max(2, labyrinth.grid.box_size / 8)));
+        cairo_context.set_line_cap(cairo::LineCap::Round);
+        // lines between the box centers, so that diagonal steps stay connected
+        for step in labyrinth.visible_path().windows(2) {
+            let (from, to) = (PointInGridCoordinates::from(step[0]), PointInGridCoordinates::from(step[1]));
+            if labyrinth.segment_to_pixel((from, to)).intersect(draw_area).is_some() {
+                let (from, to) = (labyrinth.box_center(from), labyrinth.box_center(to));
+                cairo_context.move_to(from.x(), from.y());
+                cairo_context.line_to(to.x(), to.y());
+            }
+        }
+        cairo_context.stroke();
+        cairo_context.restore();
+        Ok(())
+    }
+    // the boxes which a dragged shape will change, half transparent
+    pub fn draw_preview(
+        &self,
+        labyrinth: &Labyrinth,
+        boxes: &[PointInGridCoordinates],
+        state: BoxState,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        const PREVIEW_ALPHA: f64 = 0.5;
+        cairo_context.save();
+        Renderer::set_source_color_with_alpha(cairo_context, &state.color(), PREVIEW_ALPHA);
+        // the board may have become smaller since the shape was started
+        let on_board = boxes.iter().filter(|&&position| labyrinth.grid.contains(position));
+        for &position in on_board {
+            let rectangle: GeneralRectangle<f64> = labyrinth.box_to_pixel(position)?.to()?;
+            cairo_context.rectangle(rectangle.x(), rectangle.y(), rectangle.width(), rectangle.height());
+        }
+        cairo_context.fill();
+        cairo_context.restore();
+        Ok(())
+    }
+    // a frame inside the box under the keyboard cursor
+    pub fn draw_cursor(
+        &self,
+        labyrinth: &Labyrinth,
+        position: PointInGridCoordinates,
+        cairo_context: &cairo::Context,
+    ) -> Result<(), Error> {
+        // the board may have become smaller since the cursor was moved
+        if !labyrinth.grid.contains(position) {
+            return Ok(());
+        }
+        let rectangle: GeneralRectangle<f64> = labyrinth.box_to_pixel(position)?.to()?;
+        let line_width = f64::from(max(2, labyrinth.grid.box_size / 8));
+        cairo_context.save();
+        Renderer::set_source_color(cairo_context, &Color::get_black());
+        cairo_context.set_line_width(line_width);
+        // the frame stays inside the box, which is redrawn when the cursor moves
+        cairo_context.rectangle(
+            rectangle.x() + line_width / 2.0,
+            rectangle.y() + line_width / 2.0,
+            rectangle.width() - line_width,
+            rectangle.height() - line_width,
+        );
+        cairo_context.stroke();
+        cairo_context.restore();
+        Ok(())
+    }
+    // breadcrumbs on the boxes the player has left and the player on top
+    pub fn draw_player(&self, labyrinth: &Labyrinth, player: &Player, cairo_context: &cairo::Context) -> Result<(), Error> {
+        let radius = f64::from(labyrinth.grid.box_size) / 2.0;
+        let on_board = |&&position: &&PointInGridCoordinates| labyrinth.grid.contains(position);
+        cairo_context.save();
+        Renderer::set_source_color(cairo_context, &Color::from_tuple((255.0, 140.0, 0.0)));
+        for &position in player.trail.iter().filter(on_board) {
+            let center = labyrinth.box_center(position);
+            cairo_context.new_sub_path();
+            cairo_context.arc(center.x(), center.y(), radius / 4.0, 0.0, 2.0 * PI);
+        }
+        cairo_context.fill();
+        if let Some(&position) = Some(&player.position).filter(on_board) {
+            let center = labyrinth.box_center(position);
+            cairo_context.arc(center.x(), center.y(), radius * 0.7, 0.0, 2.0 * PI);
+            cairo_context.fill_preserve();
+            Renderer::set_source_color(cairo_context, &Color::get_black());
+            cairo_context.set_line_width(f64::from(max(1, labyrinth.grid.box_size / 16)));
+            cairo_context.stroke();
+        }
+        cairo_context.restore();
+        Ok(())
+    }
+    // the steps and the time on a light band across the middle of the board
+    pub fn draw_summary(&self, labyrinth: &Labyrinth, summary: &str, cairo_context: &cairo::Context) -> Result<(), Error> {
+        const BAND_ALPHA: f64 = 0.8;
+        let area: GeneralRectangle<f64> = labyrinth.grid.area.to()?;
+        cairo_context.save();
+        cairo_context.select_font_face("sans-serif", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
+        // the text takes most of the width of the board, but at most a third of its height
+        cairo_context.set_font_size(1.0);
+        let unit_extents = cairo_context.text_extents(summary);
+        let font_size = (0.8 * area.width() / unit_extents.width).min(area.height() / 3.0);
+        cairo_context.set_font_size(font_size);
+        let extents = cairo_context.text_extents(summary);
+        Renderer::set_source_color_with_alpha(cairo_context, &Color::get_white(), BAND_ALPHA);
+        cairo_context.rectangle(area.x(), area.y() + (area.height() - 2.0 * font_size) / 2.0, area.width(), 2.0 * font_size);
+        cairo_context.fill();
+        Renderer::set_source_color(cairo_context, &Color::get_black());
+        cairo_context.move_to(
+            area.x() + (area.width() - extents.width) / 2.0 - extents.x_bearing,
+            area.y() + (area.height() - extents.height) / 2.0 - extents.y_bearing,
+        );
+        cairo_context.show_text(summary);
+        cairo_context.restore();
+        Ok(())
+    }
+    fn set_source_color(cairo_context: &cairo::Context, color: &Color) {
+        Renderer::set_source_color_with_alpha(cairo_context, color, 1.0);
+    }
+    fn set_source_color_with_alpha(cairo_context: &cairo::Context, color: &Color, alpha: f64) {
+        // colors are stored with components from 0 to 255, cairo expects 0 to 1
+        const MAXIMUM: f64 = 255.0;
+        cairo_context.set_source_rgba(color.red() / MAXIMUM, color.green() / MAXIMUM, color.blue() / MAXIMUM, alpha);
+    }
+}
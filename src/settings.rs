@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use rand::{SeedableRng, StdRng};
+use failure::Error;
+use rustirinth_core::basic_types::LabyrinthError;
+use rustirinth_core::solver::SearchOptions;
+use rustirinth_core::generator::GeneratorKind;
+use rustirinth_core::image_import::ImportOptions;
+
+// everything which can be configured on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub box_size: u32,
+    // a fixed number of columns and rows, the box size is then chosen to
+    // fit the window
+    pub dimensions: Option<(u32, u32)>,
+    pub window_mode: WindowMode,
+    pub search_options: SearchOptions,
+    pub generator: GeneratorKind,
+    pub seed: Option<usize>,
+    pub load: Option<PathBuf>,
+    // a png which is turned into the labyrinth, see import_options
+    pub import: Option<PathBuf>,
+    pub import_options: ImportOptions,
+}
+
+impl Settings {
+    // a fixed seed makes the generated mazes reproducible
+    pub fn random_number_generator(&self) -> Result<StdRng, Error> {
+        match self.seed {
+            Some(seed) => Ok(StdRng::from_seed(&[seed][..])),
+            None => Ok(StdRng::new()?),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum WindowMode {
+    // fills the primary monitor
+    #[default]
+    Fullscreen,
+    // a normal, resizable window with the given initial size
+    Windowed { width: u32, height: u32 },
+}
+
+impl WindowMode {
+    pub const DEFAULT_WINDOWED: WindowMode = WindowMode::Windowed {
+        width: 1024,
+        height: 768,
+    };
+}
+
+// parses a geometry like "800x600"
+impl FromStr for WindowMode {
+    type Err = LabyrinthError;
+    fn from_str(geometry: &str) -> Result<WindowMode, LabyrinthError> {
+        let mut parts = geometry.splitn(2, 'x').map(|part| part.parse::<u32>());
+        match (parts.next(), parts.next()) {
+            (Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => Ok(WindowMode::Windowed { width, height }),
+            _ => Err(LabyrinthError::InvalidGeometry {
+                value: geometry.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_geometry() {
+        assert_eq!(
+            "800x600".parse::<WindowMode>().unwrap(),
+            WindowMode::Windowed {
+                width: 800,
+                height: 600,
+            }
+        );
+        assert!("800".parse::<WindowMode>().is_err());
+        assert!("0x600".parse::<WindowMode>().is_err());
+        assert!("800x600x2".parse::<WindowMode>().is_err());
+        assert!("wide x high".parse::<WindowMode>().is_err());
+    }
+}
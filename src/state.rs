@@ -0,0 +1,147 @@
+use std::cmp::{max, min};
+use std::path::PathBuf;
+use rand::StdRng;
+use failure::Error;
+use rustirinth_core::file_format;
+use rustirinth_core::generator::GeneratorKind;
+use rustirinth_core::grid::{GeneralPointInWindowPixels, PointInGridCoordinates};
+use rustirinth_core::image_import::ImportOptions;
+use rustirinth_core::labyrinth::{BoxState, Labyrinth};
+use rustirinth_core::play::Player;
+use rustirinth_core::shapes::Shape;
+use rustirinth_core::solver::SearchOptions;
+use autosave::Autosave;
+use png_import;
+use settings::Settings;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct AnimationControl {
+    // milliseconds between two steps of the animated search
+    pub interval: u32,
+    pub paused: bool,
+    pub timer_active: bool,
+}
+
+impl AnimationControl {
+    const MINIMUM_INTERVAL: u32 = 5;
+    const MAXIMUM_INTERVAL: u32 = 2000;
+    pub fn faster(&mut self) {
+        self.interval = max(AnimationControl::MINIMUM_INTERVAL, self.interval / 2);
+    }
+    pub fn slower(&mut self) {
+        self.interval = min(AnimationControl::MAXIMUM_INTERVAL, self.interval * 2);
+    }
+}
+
+impl Default for AnimationControl {
+    fn default() -> AnimationControl {
+        AnimationControl {
+            interval: 80,
+            paused: false,
+            timer_active: false,
+        }
+    }
+}
+
+// how the mouse paints on the board
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Tool {
+    // box by box while the button is held down
+    #[default]
+    Pencil,
+    // from the box where the button was pressed to the one where it is released
+    Shape(Shape),
+    // the connected region of boxes in the same state as the clicked one
+    Bucket,
+}
+
+// a shape which is being dragged, shown as a preview until the button is released
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ShapeDrag {
+    pub shape: Shape,
+    pub state: BoxState,
+    pub button: u32,
+    pub from: PointInGridCoordinates,
+    pub to: PointInGridCoordinates,
+}
+
+impl ShapeDrag {
+    pub fn boxes(&self) -> Vec<PointInGridCoordinates> {
+        let boxes = self.shape.boxes(self.from.coordinate, self.to.coordinate);
+        boxes.into_iter().map(PointInGridCoordinates::from).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct LabyrinthState {
+    pub box_size: u32,
+    pub dimensions: Option<(u32, u32)>,
+    pub search_options: SearchOptions,
+    pub brush: BoxState,
+    pub tool: Tool,
+    pub drag: Option<ShapeDrag>,
+    // the box edited with the keyboard, shown once a cursor key was pressed
+    pub cursor: Option<PointInGridCoordinates>,
+    // shift + cursor keys paint, everything until shift is released is one undo step
+    pub painting_with_keys: bool,
+    // the walk through the labyrinth in play mode, None while editing
+    pub player: Option<Player>,
+    pub animation: AnimationControl,
+    pub generator: GeneratorKind,
+    // used for --import and Ctrl+I
+    pub import_options: ImportOptions,
+    pub rng: StdRng,
+    // the file which was loaded or saved the last time
+    pub file: Option<PathBuf>,
+    // loaded before the size of the drawing area is known
+    pub loaded: Option<Labyrinth>,
+    // the labyrinth is larger than the drawing area
+    pub too_small: bool,
+    // where the view was dragged to with the middle mouse button
+    pub pan_anchor: Option<GeneralPointInWindowPixels<f64>>,
+    pub autosave: Autosave,
+    pub labyrinth: Option<Labyrinth>,
+}
+
+impl LabyrinthState {
+    pub fn new(settings: Settings) -> Result<LabyrinthState, Error> {
+        let loaded = match (settings.load.as_ref(), settings.import.as_ref()) {
+            (Some(path), _) => Some(file_format::load(path)?),
+            (None, Some(path)) => Some(png_import::load(path, settings.box_size, &settings.import_options)?),
+            (None, None) => None,
+        };
+        // a loaded labyrinth keeps its number of boxes
+        let dimensions = match loaded {
+            Some(ref labyrinth) => Some((labyrinth.grid.x_box_cnt, labyrinth.grid.y_box_cnt)),
+            None => settings.dimensions,
+        };
+        Ok(LabyrinthState {
+            box_size: settings.box_size,
+            dimensions,
+            search_options: settings.search_options,
+            brush: BoxState::Labyrinth,
+            tool: Tool::default(),
+            drag: None,
+            cursor: None,
+            painting_with_keys: false,
+            player: None,
+            animation: AnimationControl::default(),
+            generator: settings.generator,
+            import_options: settings.import_options,
+            rng: settings.random_number_generator()?,
+            file: settings.load,
+            loaded,
+            too_small: false,
+            pan_anchor: None,
+            autosave: Autosave::new(),
+            labyrinth: None,
+        })
+    }
+    pub fn needs_animation_timer(&self) -> bool {
+        let animating = match self.labyrinth {
+            Some(ref labyrinth) => labyrinth.is_animating(),
+            None => false,
+        };
+        animating && !self.animation.timer_active && !self.animation.paused
+    }
+}